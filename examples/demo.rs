@@ -5,6 +5,8 @@ mod support;
 fn main() {
     let system = support::init(file!());
     let mut values: Vec<f32> = vec![0.0; 7];
+    let mut waveform = 0;
+    let mut phase = 0.0;
     let min = -6.0;
     let max = 6.0;
     let default = 0.0;
@@ -149,16 +151,13 @@ fn main() {
                 ui.next_column();
 
                 draw_stepped_knob(
-                    &knob_with_drag(
-                        ui,
-                        im_str!("Knob7"),
-                        im_str!("Gain7"),
-                        &mut values[6],
-                        min,
-                        max,
-                        default,
-                        format,
-                    ),
+                    &Knob::builder(im_str!("Knob7"), &mut values[6])
+                        .range(min, max)
+                        .default(default)
+                        .steps(Steps::Count(7))
+                        .title(im_str!("Gain7"))
+                        .drag(format)
+                        .build(ui),
                     7,
                     &base,
                     &highlight,
@@ -167,6 +166,40 @@ fn main() {
 
                 ui.next_column();
 
+                ui.columns(1, im_str!("selector"), false);
+                ui.separator();
+
+                selector_knob(
+                    ui,
+                    im_str!("Waveform"),
+                    &mut waveform,
+                    &[
+                        im_str!("Sine"),
+                        im_str!("Saw"),
+                        im_str!("Square"),
+                        im_str!("Triangle"),
+                    ],
+                    ui.text_line_height() * 2.0,
+                    &KnobColors::new(base, highlight, lowlight),
+                );
+
+                ui.same_line(0.0);
+                let theme = push_knob_theme(
+                    ui,
+                    KnobTheme::new(KnobColors::new(base, highlight, lowlight)),
+                );
+                ui.group(|| {
+                    Knob::builder(im_str!("Phase"), &mut phase)
+                        .range(0.0, 360.0)
+                        .encoder(EncoderMode::Endless)
+                        .description(im_str!("Oscillator start phase"))
+                        .title(im_str!("Phase"))
+                        .drag(im_str!("%.0f deg"))
+                        .style(KnobVariant::Dot)
+                        .build(ui);
+                });
+                theme.pop(ui);
+
                 colors.into_iter().for_each(|color| color.pop(ui));
             });
     });
@@ -8,7 +8,6 @@ fn main() {
     let min = -6.0;
     let max = 6.0;
     let default = 0.0;
-    let format = im_str!("%.2fdB");
 
     system.main_loop(move |_, ui| {
         Window::new(im_str!("Hello Knob"))
@@ -29,21 +28,17 @@ fn main() {
                 );
                 let lowlight = ColorSet::from([0.0, 0.0, 0.0, 1.0]);
 
-                draw_wiper_knob(
-                    &knob_with_drag(
-                        ui,
-                        im_str!("Knob"),
-                        im_str!("Gain"),
-                        &mut value,
-                        min,
-                        max,
-                        default,
-                        format,
-                    ),
-                    &base,
-                    &highlight,
-                    &lowlight,
-                );
+                Knob::builder(im_str!("Knob"), &mut value)
+                    .range(min, max)
+                    .default(default)
+                    .wiper_origin(WiperOrigin::Default)
+                    .context_menu(true)
+                    .title(im_str!("Gain"))
+                    .drag(im_str!("%.2f dB"))
+                    .formatter(&DecibelFormat { precision: 2 })
+                    .style(KnobVariant::Wiper)
+                    .colors(KnobColors::new(base, highlight, lowlight))
+                    .build(ui);
 
                 ui.next_column();
             });
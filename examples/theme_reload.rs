@@ -0,0 +1,60 @@
+//! Draws a few knobs with a theme loaded from `examples/knob_theme.toml`, reloading it
+//! whenever the file changes on disk. Run with `cargo run --example theme_reload --features toml`.
+
+use imgui::*;
+use imgui_knobs::*;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+mod support;
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn main() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/knob_theme.toml");
+    let system = support::init(file!());
+    let mut values: Vec<f32> = vec![0.0; 3];
+    let mut theme = KnobTheme::new(KnobColors::default());
+    let mut loaded_at = None;
+    let mut error = None;
+
+    system.main_loop(move |_, ui| {
+        let modified_at = modified(&path);
+        if modified_at != loaded_at {
+            loaded_at = modified_at;
+            match KnobTheme::load(&path) {
+                Ok(loaded) => {
+                    theme = loaded;
+                    error = None;
+                }
+                Err(e) => error = Some(ImString::new(e.to_string())),
+            }
+        }
+
+        Window::new(im_str!("Theme Reload"))
+            .size([400.0, 300.0], Condition::FirstUseEver)
+            .position([20.0, 20.0], Condition::Appearing)
+            .build(ui, || {
+                ui.text(path.display().to_string());
+                if let Some(error) = &error {
+                    ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
+                }
+                ui.separator();
+
+                ui.columns(3, im_str!("cols"), false);
+                for (n, value) in values.iter_mut().enumerate() {
+                    Knob::builder(&ImString::new(format!("Knob{}", n)), value)
+                        .range(-6.0, 6.0)
+                        .title(im_str!("Gain"))
+                        .formatter(&DecibelFormat::default())
+                        .theme(&theme)
+                        .build(ui);
+                    ui.next_column();
+                }
+            });
+    });
+}
@@ -0,0 +1,190 @@
+use crate::*;
+
+/// Shape of the ends of an arc.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ArcCap {
+    /// Cut square at the start and end angles.
+    #[default]
+    Butt,
+    /// Half circles past the start and end angles.
+    Round,
+}
+
+/// Largest distance in pixels between the drawn arc and the true circle.
+const ARC_TOLERANCE: f32 = 0.25;
+
+/// Vertices and triangles of an arc before they are copied into the draw list.
+struct Mesh {
+    vertices: Vec<([f32; 2], u32)>,
+    indices: Vec<u32>,
+}
+
+impl Mesh {
+    fn vertex(&mut self, pos: [f32; 2], color: u32) -> u32 {
+        self.vertices.push((pos, color));
+        self.vertices.len() as u32 - 1
+    }
+
+    fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    fn quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.triangle(a, b, c);
+        self.triangle(a, c, d);
+    }
+}
+
+fn segments_for(radius: f32, sweep: f32) -> u32 {
+    let step = 2.0 * (1.0 - (ARC_TOLERANCE / radius.max(ARC_TOLERANCE)).min(1.0)).acos();
+    (sweep.abs() / step.max(0.01)).ceil() as u32
+}
+
+/// Draws an arc into the current window's draw list as a single triangle strip, with
+/// anti-aliased edges when the draw list has anti-aliased fills enabled. The segment count
+/// follows from the radius, at least `min_segments`, and no pixel is covered twice, so
+/// translucent colors blend evenly.
+pub fn draw_arc_strip(
+    center: [f32; 2],
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    thickness: f32,
+    color: [f32; 4],
+    min_segments: u32,
+    cap: ArcCap,
+) {
+    let sweep = end_angle - start_angle;
+    if thickness <= 0.0 || (sweep == 0.0 && cap == ArcCap::Butt) {
+        return;
+    }
+
+    let draw_list = unsafe { sys::igGetWindowDrawList() };
+    let anti_aliased = unsafe {
+        (*draw_list).Flags & sys::ImDrawListFlags_AntiAliasedFill as sys::ImDrawListFlags != 0
+    };
+    let fringe = if anti_aliased {
+        unsafe { (*draw_list)._FringeScale }
+    } else {
+        0.0
+    };
+    let solid: u32 = ImColor32::from(color).into();
+    let clear: u32 = ImColor32::from([color[0], color[1], color[2], 0.0]).into();
+
+    let half = thickness * 0.5;
+    let outer = radius + half;
+    let inner = radius - half;
+    //Rows of vertices across the band, from the outside in. Anti-aliasing fades the edges
+    //over one fringe width centered on them.
+    let rows: Vec<(f32, u32)> = if anti_aliased {
+        vec![
+            (outer + fringe * 0.5, clear),
+            (outer - fringe * 0.5, solid),
+            ((inner + fringe * 0.5).max(0.0), solid),
+            ((inner - fringe * 0.5).max(0.0), clear),
+        ]
+    } else {
+        vec![(outer, solid), (inner.max(0.0), solid)]
+    };
+    let row_count = rows.len() as u32;
+    //Capped after applying the minimum, which comes from the caller and may be larger
+    let segments = segments_for(outer, sweep).max(min_segments.max(1)).min(512);
+
+    let mut mesh = Mesh {
+        vertices: Vec::new(),
+        indices: Vec::new(),
+    };
+    for n in 0..=segments {
+        let angle = start_angle + sweep * n as f32 / segments as f32;
+        let (sin, cos) = angle.sin_cos();
+        for &(row_radius, row_color) in rows.iter() {
+            mesh.vertex(
+                [center[0] + cos * row_radius, center[1] + sin * row_radius],
+                row_color,
+            );
+        }
+    }
+    for n in 0..segments {
+        for row in 0..row_count - 1 {
+            let a = n * row_count + row;
+            mesh.quad(a, a + 1, a + row_count + 1, a + row_count);
+        }
+    }
+
+    let direction = if sweep < 0.0 { -1.0 } else { 1.0 };
+    for &(angle, column, outward) in [
+        (start_angle, 0, -direction),
+        (end_angle, segments * row_count, direction),
+    ]
+    .iter()
+    {
+        let (sin, cos) = angle.sin_cos();
+        let radial = [cos, sin];
+        let tangent = [-sin * outward, cos * outward];
+        match cap {
+            ArcCap::Butt => {
+                if anti_aliased {
+                    //Fade the cut ends out over one fringe width
+                    let first = mesh.vertices.len() as u32;
+                    for row in 0..row_count {
+                        let (pos, _) = mesh.vertices[(column + row) as usize];
+                        mesh.vertex(
+                            [pos[0] + tangent[0] * fringe, pos[1] + tangent[1] * fringe],
+                            clear,
+                        );
+                    }
+                    for row in 0..row_count - 1 {
+                        mesh.quad(column + row, first + row, first + row + 1, column + row + 1);
+                    }
+                }
+            }
+            ArcCap::Round => {
+                let end = [center[0] + cos * radius, center[1] + sin * radius];
+                let steps = segments_for(half, PI).clamp(4, 64);
+                let middle = mesh.vertex(end, solid);
+                let mut previous: Option<(u32, u32)> = None;
+                for n in 0..=steps {
+                    let phi = PI * n as f32 / steps as f32;
+                    let (phi_sin, phi_cos) = phi.sin_cos();
+                    let dir = [
+                        radial[0] * phi_cos + tangent[0] * phi_sin,
+                        radial[1] * phi_cos + tangent[1] * phi_sin,
+                    ];
+                    let edge = (half - fringe * 0.5).max(0.0);
+                    let rim = mesh.vertex([end[0] + dir[0] * edge, end[1] + dir[1] * edge], solid);
+                    let faded = if anti_aliased {
+                        let edge = half + fringe * 0.5;
+                        mesh.vertex([end[0] + dir[0] * edge, end[1] + dir[1] * edge], clear)
+                    } else {
+                        rim
+                    };
+                    if let Some((last_rim, last_faded)) = previous {
+                        mesh.triangle(middle, last_rim, rim);
+                        if anti_aliased {
+                            mesh.quad(last_rim, last_faded, faded, rim);
+                        }
+                    }
+                    previous = Some((rim, faded));
+                }
+            }
+        }
+    }
+
+    unsafe {
+        let mut uv = sys::ImVec2::zero();
+        sys::igGetFontTexUvWhitePixel(&mut uv);
+        sys::ImDrawList_PrimReserve(
+            draw_list,
+            mesh.indices.len() as i32,
+            mesh.vertices.len() as i32,
+        );
+        let base = (*draw_list)._VtxCurrentIdx;
+        for &(pos, color) in mesh.vertices.iter() {
+            sys::ImDrawList_PrimWriteVtx(draw_list, pos.into(), uv, color);
+        }
+        for &index in mesh.indices.iter() {
+            sys::ImDrawList_PrimWriteIdx(draw_list, (base + index) as sys::ImDrawIdx);
+        }
+    }
+}
@@ -0,0 +1,1060 @@
+use crate::state::{KnobState, Slot};
+use crate::*;
+
+type MenuItems<'a> = Box<dyn FnMut(&Ui) + 'a>;
+
+/// Builder for a knob, covering the control, the optional title and drag widget and the
+/// drawing of one of the built in `KnobStyle`s.
+#[must_use]
+pub struct KnobBuilder<'a, T: KnobValue = f32> {
+    label: &'a ImStr,
+    p_value: &'a mut T,
+    v_min: T,
+    v_max: T,
+    v_default: Option<T>,
+    radius: Option<f32>,
+    angle_min: f32,
+    angle_max: f32,
+    wiper_origin: WiperOrigin,
+    encoder: EncoderMode,
+    /// Knob position for `EncoderMode::Relative`, where the value only holds the change.
+    position: Option<f64>,
+    taper: Taper<'a>,
+    steps: Steps,
+    sensitivity: Option<Sensitivity>,
+    drag_mode: DragMode,
+    reset: ResetGestures,
+    wheel_mode: WheelMode,
+    wheel_step: f32,
+    keyboard: bool,
+    key_step: f32,
+    context_menu: bool,
+    menu_items: Option<MenuItems<'a>>,
+    /// Fine adjustment toggled from the context menu.
+    fine: bool,
+    focus_ring: bool,
+    controllable: bool,
+    /// Copied so title and format may be temporaries, they're only used while building.
+    title: Option<ImString>,
+    drag: bool,
+    drag_format: Option<ImString>,
+    formatter: Option<&'a dyn ValueFormat>,
+    tooltip: bool,
+    description: Option<&'a ImStr>,
+    style: Option<KnobStyle>,
+    layers: Option<&'a [KnobLayer]>,
+    colors: Option<KnobColors>,
+}
+
+impl<'a, T: KnobValue> KnobBuilder<'a, T> {
+    pub fn new(label: &'a ImStr, p_value: &'a mut T) -> KnobBuilder<'a, T> {
+        KnobBuilder {
+            label,
+            p_value,
+            v_min: T::from_f64(0.0),
+            v_max: T::from_f64(1.0),
+            v_default: None,
+            radius: None,
+            angle_min: PI * 0.75,
+            angle_max: PI * 2.25,
+            wiper_origin: WiperOrigin::Min,
+            encoder: EncoderMode::Absolute,
+            position: None,
+            taper: Taper::Linear,
+            steps: Steps::Continuous,
+            sensitivity: None,
+            drag_mode: DragMode::Vertical,
+            reset: ResetGestures::DOUBLE_CLICK,
+            wheel_mode: WheelMode::Auto,
+            wheel_step: 0.05,
+            keyboard: true,
+            key_step: 0.01,
+            context_menu: false,
+            menu_items: None,
+            fine: false,
+            focus_ring: true,
+            controllable: true,
+            title: None,
+            drag: false,
+            drag_format: None,
+            formatter: None,
+            tooltip: false,
+            description: None,
+            style: None,
+            layers: None,
+            colors: None,
+        }
+    }
+
+    pub fn range(mut self, v_min: T, v_max: T) -> Self {
+        self.v_min = v_min;
+        self.v_max = v_max;
+        self
+    }
+
+    /// Value restored by the reset gestures, defaults to `v_min`.
+    pub fn default(mut self, v_default: T) -> Self {
+        self.v_default = Some(v_default);
+        self
+    }
+
+    /// Defaults to two text line heights, the size used by `knob_with_drag`.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    /// Start and end angles of the sweep in radians, clockwise from the positive x axis.
+    /// Defaults to a 270 degree sweep with the gap at the bottom, `PI * 0.75` to `PI * 2.25`.
+    /// A sweep of `PI * 2.0` gives a full circle.
+    pub fn angles(mut self, angle_min: f32, angle_max: f32) -> Self {
+        self.angle_min = angle_min;
+        self.angle_max = angle_max;
+        self
+    }
+
+    pub fn wiper_origin(mut self, wiper_origin: WiperOrigin) -> Self {
+        self.wiper_origin = wiper_origin;
+        self
+    }
+
+    /// Endless knobs always sweep a full circle starting at `angle_min`.
+    pub fn encoder(mut self, encoder: EncoderMode) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    pub fn taper(mut self, taper: Taper<'a>) -> Self {
+        self.taper = taper;
+        self
+    }
+
+    pub fn steps(mut self, steps: Steps) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Defaults to the global `default_sensitivity()`.
+    pub fn sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.sensitivity = Some(sensitivity);
+        self
+    }
+
+    pub fn drag_mode(mut self, drag_mode: DragMode) -> Self {
+        self.drag_mode = drag_mode;
+        self
+    }
+
+    pub fn reset(mut self, reset: ResetGestures) -> Self {
+        self.reset = reset;
+        self
+    }
+
+    pub fn wheel_mode(mut self, wheel_mode: WheelMode) -> Self {
+        self.wheel_mode = wheel_mode;
+        self
+    }
+
+    /// Fraction of the sweep moved per wheel notch, defaults to 0.05. Stepped knobs move
+    /// one step per notch instead.
+    pub fn wheel_step(mut self, wheel_step: f32) -> Self {
+        self.wheel_step = wheel_step;
+        self
+    }
+
+    /// Space or Enter activate a focused knob, the arrow keys, PageUp/PageDown and Home/End
+    /// then adjust it until Space or Escape. Imgui's navigation settings are left as they
+    /// are, with keyboard navigation on an arrow key can still move focus away, which
+    /// deactivates the knob. Enter on an active knob, Ctrl+click or double-click with a
+    /// modifier held open a text input taking values with units, see `parse_value`, as do
+    /// Ctrl+click and double-click on the drag widget. The gamepad left stick adjusts a
+    /// focused knob. Enabled by default.
+    pub fn keyboard(mut self, keyboard: bool) -> Self {
+        self.keyboard = keyboard;
+        self
+    }
+
+    /// Fraction of the sweep moved per arrow key press, defaults to 0.01. PageUp and
+    /// PageDown move ten times as far, stepped knobs move by whole steps.
+    pub fn key_step(mut self, key_step: f32) -> Self {
+        self.key_step = key_step;
+        self
+    }
+
+    /// Opens a menu on right-click with entries to reset the knob, type a value, copy and
+    /// paste the value and toggle fine adjustment.
+    pub fn context_menu(mut self, context_menu: bool) -> Self {
+        self.context_menu = context_menu;
+        self
+    }
+
+    /// Extra entries added to the end of the context menu, enables the menu.
+    pub fn context_menu_items(mut self, items: impl FnMut(&Ui) + 'a) -> Self {
+        self.context_menu = true;
+        self.menu_items = Some(Box::new(items));
+        self
+    }
+
+    /// Draws a ring around the knob while it has keyboard or gamepad focus.
+    pub fn focus_ring(mut self, focus_ring: bool) -> Self {
+        self.focus_ring = focus_ring;
+        self
+    }
+
+    pub fn controllable(mut self, controllable: bool) -> Self {
+        self.controllable = controllable;
+        self
+    }
+
+    /// Centered text drawn above the knob.
+    pub fn title(mut self, title: &ImStr) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Adds a `Drag` widget below the knob using the given printf style format, or the knob's
+    /// formatter when it has one. Ctrl+click or double-click on it opens the knob's text input.
+    /// Knobs in `EncoderMode::Relative` have no drag widget since the value only holds the
+    /// change.
+    pub fn drag(mut self, format: &ImStr) -> Self {
+        self.drag = true;
+        self.drag_format = Some(format.to_owned());
+        self
+    }
+
+    /// Formats the value for the drag widget when there is one, the tooltip and the text
+    /// input. Typed and pasted text is parsed with it first, then with `parse_value`.
+    pub fn formatter(mut self, formatter: &'a dyn ValueFormat) -> Self {
+        self.formatter = Some(formatter);
+        self
+    }
+
+    /// Shows the value in a tooltip while the knob is hovered or dragged, formatted with the
+    /// knob's formatter when it has one.
+    pub fn tooltip(mut self, tooltip: bool) -> Self {
+        self.tooltip = tooltip;
+        self
+    }
+
+    /// Help text shown above the value in the tooltip, enables the tooltip.
+    pub fn description(mut self, description: &'a ImStr) -> Self {
+        self.tooltip = true;
+        self.description = Some(description);
+        self
+    }
+
+    /// Draws the knob in one of the built in styles, either a `KnobVariant` or a `KnobStyle`
+    /// with adjusted proportions. Without a style nothing is drawn and the returned `Knob`
+    /// can be passed to any of the `draw_*_knob` functions.
+    pub fn style(mut self, style: impl Into<KnobStyle>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
+    /// Draws a custom design made of layers, after the style when one is set.
+    pub fn layers(mut self, layers: &'a [KnobLayer]) -> Self {
+        self.layers = Some(layers);
+        self
+    }
+
+    /// Defaults to the colors of the current `KnobTheme`.
+    pub fn colors(mut self, colors: KnobColors) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    /// Draws the knob with the theme's style and colors.
+    pub fn theme(self, theme: &KnobTheme) -> Self {
+        self.style(theme.style).colors(theme.colors)
+    }
+
+    fn has_drag(&self) -> bool {
+        self.drag && self.encoder != EncoderMode::Relative
+    }
+
+    fn radius_or_default(&self, ui: &Ui) -> f32 {
+        self.radius.unwrap_or_else(|| ui.text_line_height() * 2.0)
+    }
+
+    /// Adds the knob's invisible button and applies mouse input to the value without
+    /// creating a `Knob` or drawing anything. Returns true if the value changed.
+    pub fn build_control(mut self, ui: &Ui) -> bool {
+        let radius = self.radius_or_default(ui);
+        self.load_state(ui);
+        self.control(ui, radius).value_changed
+    }
+
+    fn load_state(&mut self, ui: &Ui) {
+        let state = KnobState::new(ui, self.label);
+        if self.encoder == EncoderMode::Relative {
+            let position = state.get_f64(Slot::PositionLow, Slot::PositionHigh);
+            self.position = Some(self.limit(position));
+        }
+        self.fine = self.context_menu && state.get_bool(Slot::Fine);
+    }
+
+    fn control(&mut self, ui: &Ui, radius: f32) -> ControlState {
+        let before = self.value();
+        let mut control = self.input(ui, radius);
+        control.delta = self.difference(before, self.value());
+
+        let state = KnobState::new(ui, self.label);
+        if control.value_changed && !control.is_active && !control.edit_finished {
+            control.edit_started = true;
+            control.edit_finished = true;
+        }
+        if control.edit_started {
+            state.set_f64(Slot::EditStartLow, Slot::EditStartHigh, before);
+            state.set_bool(Slot::HasEditStart, true);
+        }
+        control.edit_start = if state.get_bool(Slot::HasEditStart) {
+            state.get_f64(Slot::EditStartLow, Slot::EditStartHigh)
+        } else {
+            before
+        };
+
+        if let Some(position) = self.position {
+            state.set_f64(Slot::PositionLow, Slot::PositionHigh, position);
+            *self.p_value = T::from_f64(control.delta);
+        }
+        control
+    }
+
+    fn input(&mut self, ui: &Ui, radius: f32) -> ControlState {
+        ui.invisible_button(self.label, [radius * 2.0, radius * 2.0]);
+
+        let state = KnobState::new(ui, self.label);
+        let mut control = ControlState {
+            value_changed: false,
+            editing: false,
+            was_reset: false,
+            edit_started: ui.is_item_activated(),
+            edit_finished: ui.is_item_deactivated(),
+            edit_start: 0.0,
+            delta: 0.0,
+            is_active: ui.is_item_active(),
+            is_hovered: ui.is_item_hovered(),
+            is_focused: ui.is_item_focused(),
+        };
+        let io = ui.io();
+
+        if self.reset_input(ui, control.is_active) {
+            let v_default = self.v_default.unwrap_or(self.v_min);
+            control.value_changed = self.set_value(v_default.to_f64());
+            control.was_reset = true;
+            return control;
+        }
+
+        if self.context_menu || self.reset.context_menu {
+            let (reset, value_changed) = self.context_menu_input(ui, &state);
+            if reset {
+                let v_default = self.v_default.unwrap_or(self.v_min);
+                control.value_changed = self.set_value(v_default.to_f64());
+                control.was_reset = true;
+                return control;
+            }
+            control.value_changed = value_changed;
+        }
+
+        let mut editing = state.get_bool(Slot::Editing);
+        let mut adjusting =
+            self.keyboard && !editing && control.is_focused && state.get_bool(Slot::Adjusting);
+        if self.keyboard && !editing {
+            let ctrl_click = ui.is_item_clicked(MouseButton::Left) && io.key_ctrl;
+            let double_click = control.is_hovered
+                && ui.is_mouse_double_clicked(MouseButton::Left)
+                && Modifiers::ANY.is_held(io);
+            let keys = control.is_focused && !io.want_text_input;
+            let enter =
+                keys && (key_pressed_once(Key::Enter) || key_pressed_once(Key::KeyPadEnter));
+            let space = keys && key_pressed_once(Key::Space);
+            let escape = keys && key_pressed_once(Key::Escape);
+            if ctrl_click || double_click || (enter && adjusting) {
+                editing = true;
+                adjusting = false;
+                state.set_bool(Slot::Editing, true);
+                state.set_bool(Slot::EditFocus, true);
+            } else if adjusting {
+                adjusting = !(space || escape);
+            } else {
+                adjusting = enter || space;
+            }
+        }
+        state.set_bool(Slot::Adjusting, adjusting);
+        if adjusting {
+            //Keeps the application from also acting on the keys
+            unsafe { sys::igCaptureKeyboardFromApp(true) };
+        }
+
+        if editing {
+            control.value_changed |= self.text_input(ui, &state, radius);
+            control.editing = true;
+            return control;
+        }
+
+        control.value_changed |= self.mouse_input(ui, radius, control.is_active);
+
+        let wheel = io.mouse_wheel;
+        if control.is_hovered
+            && !control.is_active
+            && wheel != 0.0
+            && self.wheel_mode.is_enabled(ui)
+        {
+            let t_step = self.wheel_step * self.scale(io);
+            if self.effective_steps().is_continuous() {
+                control.value_changed |= self.offset(wheel, t_step);
+            } else {
+                //Smooth scrolling gives fractions of a notch, collect them into whole steps
+                let accumulated = state.get_f32(Slot::Wheel) + wheel;
+                let whole = accumulated.trunc();
+                state.set_f32(Slot::Wheel, accumulated - whole);
+                if whole != 0.0 {
+                    control.value_changed |= self.offset(whole, t_step);
+                }
+            }
+        }
+
+        if self.keyboard && control.is_focused && !control.is_active && !io.want_text_input {
+            control.value_changed |= self.keyboard_input(ui, &state, adjusting);
+        }
+
+        control
+    }
+
+    fn current_sensitivity(&self) -> Sensitivity {
+        self.sensitivity.unwrap_or_else(default_sensitivity)
+    }
+
+    /// Sensitivity scale from the held modifiers, or the fine scale while fine adjustment is
+    /// toggled on.
+    fn scale(&self, io: &Io) -> f32 {
+        let sensitivity = self.current_sensitivity();
+        if self.fine {
+            1.0 / sensitivity.fine_divisor
+        } else {
+            sensitivity.scale(io)
+        }
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (self.v_min.to_f64(), self.v_max.to_f64())
+    }
+
+    /// Start and end angles, widened to a full circle for endless knobs.
+    fn sweep(&self) -> (f32, f32) {
+        if self.encoder.is_endless() {
+            let direction = if self.angle_max < self.angle_min {
+                -1.0
+            } else {
+                1.0
+            };
+            (self.angle_min, self.angle_min + PI * 2.0 * direction)
+        } else {
+            (self.angle_min, self.angle_max)
+        }
+    }
+
+    fn value(&self) -> f64 {
+        self.position.unwrap_or_else(|| self.p_value.to_f64())
+    }
+
+    fn t(&self) -> f32 {
+        let (v_min, v_max) = self.bounds();
+        self.taper.normalize(self.value(), v_min, v_max)
+    }
+
+    fn origin_t(&self) -> f32 {
+        let (v_min, v_max) = self.bounds();
+        match self.wiper_origin {
+            WiperOrigin::Min => 0.0,
+            WiperOrigin::Center => 0.5,
+            WiperOrigin::Max => 1.0,
+            WiperOrigin::Default => {
+                let v_default = self.v_default.unwrap_or(self.v_min).to_f64();
+                self.taper.normalize(v_default, v_min, v_max)
+            }
+            WiperOrigin::Value(value) => self.taper.normalize(value, v_min, v_max),
+        }
+    }
+
+    /// Integer knobs without explicit steps move in increments of one.
+    fn effective_steps(&self) -> Steps {
+        if T::IS_INTEGER && self.steps.is_continuous() {
+            Steps::Increment(1.0)
+        } else {
+            self.steps
+        }
+    }
+
+    /// Clamps the value to the range, or wraps it around for endless knobs.
+    fn limit(&self, value: f64) -> f64 {
+        let (v_min, v_max) = self.bounds();
+        let (low, high) = (v_min.min(v_max), v_min.max(v_max));
+        if !self.encoder.is_endless() {
+            return value.max(low).min(high);
+        }
+        if high <= low {
+            return low;
+        }
+        let value = if T::IS_INTEGER { value.round() } else { value };
+        low + (value - low).rem_euclid(high - low)
+    }
+
+    /// Change from `from` to `to`, the shortest way around for endless knobs.
+    fn difference(&self, from: f64, to: f64) -> f64 {
+        let delta = to - from;
+        if !self.encoder.is_endless() {
+            return delta;
+        }
+        let (v_min, v_max) = self.bounds();
+        let range = (v_max - v_min).abs();
+        if delta > range * 0.5 {
+            delta - range
+        } else if delta < -range * 0.5 {
+            delta + range
+        } else {
+            delta
+        }
+    }
+
+    /// Limits the value to the range, converts to `T` and stores it. Returns true if it changed.
+    fn set_value(&mut self, value: f64) -> bool {
+        let value = self.limit(value);
+        if let Some(position) = self.position {
+            self.position = Some(value);
+            return value != position;
+        }
+        let value = T::from_f64(value);
+        if value != *self.p_value {
+            *self.p_value = value;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn offset(&mut self, count: f32, t_step: f32) -> bool {
+        let (v_min, v_max) = self.bounds();
+        if self.encoder.is_endless() {
+            //Move past the ends before snapping so the value wraps around instead of stopping
+            return match self.effective_steps() {
+                Steps::Increment(inc) if inc > 0.0 => {
+                    let direction = if v_max < v_min { -1.0 } else { 1.0 };
+                    let value = self.limit(self.value() + count as f64 * inc * direction);
+                    let value = self.steps.snap(value, v_min, v_max, &self.taper);
+                    self.set_value(value)
+                }
+                Steps::Count(n) if n >= 2 => self.set_t(self.t() + count / (n - 1) as f32),
+                _ => self.set_t(self.t() + count * t_step),
+            };
+        }
+        let value =
+            self.effective_steps()
+                .offset(self.value(), count, t_step, v_min, v_max, &self.taper);
+        self.set_value(value)
+    }
+
+    fn set_t(&mut self, t: f32) -> bool {
+        let (v_min, v_max) = self.bounds();
+        let t = if self.encoder.is_endless() {
+            t.rem_euclid(1.0)
+        } else {
+            t
+        };
+        let value = self.taper.denormalize(t, v_min, v_max);
+        let value = self
+            .effective_steps()
+            .snap(value, v_min, v_max, &self.taper);
+        self.set_value(value)
+    }
+
+    /// Returns true when one of the enabled reset gestures was made on the knob.
+    fn reset_input(&mut self, ui: &Ui, is_active: bool) -> bool {
+        let io = ui.io();
+        let clicked = ui.is_item_clicked(MouseButton::Left);
+        let double_click = is_active
+            && ui.is_mouse_double_clicked(MouseButton::Left)
+            && !(self.keyboard && Modifiers::ANY.is_held(io));
+        (self.reset.double_click && double_click)
+            || (self.reset.ctrl_click && clicked && io.key_ctrl)
+            || (self.reset.alt_click && clicked && io.key_alt)
+    }
+
+    /// Popup opened by right-clicking the knob's invisible button, so it shares the knob's id.
+    /// Returns whether reset was chosen and whether the value changed.
+    fn context_menu_input(&mut self, ui: &Ui, state: &KnobState) -> (bool, bool) {
+        let open = unsafe {
+            sys::igBeginPopupContextItem(
+                std::ptr::null(),
+                sys::ImGuiPopupFlags_MouseButtonRight as i32,
+            )
+        };
+        if !open {
+            return (false, false);
+        }
+
+        let mut reset = false;
+        let mut value_changed = false;
+        if self.reset != ResetGestures::NONE {
+            reset = MenuItem::new(im_str!("Reset")).build(ui);
+        }
+        if self.context_menu {
+            if MenuItem::new(im_str!("Type Value")).build(ui) {
+                state.set_bool(Slot::Editing, true);
+                state.set_bool(Slot::EditFocus, true);
+            }
+            if MenuItem::new(im_str!("Copy")).build(ui) {
+                ui.set_clipboard_text(&ImString::new(self.value_text()));
+            }
+            if MenuItem::new(im_str!("Paste")).build(ui) {
+                if let Some(text) = ui.clipboard_text() {
+                    value_changed = self.set_text(text.to_str());
+                }
+            }
+            if MenuItem::new(im_str!("Fine Adjustment"))
+                .selected(self.fine)
+                .build(ui)
+            {
+                self.fine = !self.fine;
+                state.set_bool(Slot::Fine, self.fine);
+            }
+            if let Some(items) = self.menu_items.as_mut() {
+                ui.separator();
+                items(ui);
+            }
+        }
+
+        unsafe { sys::igEndPopup() };
+        (reset, value_changed)
+    }
+
+    fn value_text(&self) -> String {
+        match self.formatter {
+            Some(formatter) => formatter.format(self.value()),
+            None => T::from_f64(self.value()).to_string(),
+        }
+    }
+
+    /// Parses, limits and snaps a typed or pasted value. Returns true if the value changed.
+    fn set_text(&mut self, text: &str) -> bool {
+        let (v_min, v_max) = self.bounds();
+        let value = self
+            .formatter
+            .and_then(|formatter| formatter.parse(text))
+            .or_else(|| parse_value(text, &self.taper, v_min, v_max));
+        match value {
+            Some(value) => {
+                let value = self.limit(value);
+                let value = self
+                    .effective_steps()
+                    .snap(value, v_min, v_max, &self.taper);
+                self.set_value(value)
+            }
+            None => false,
+        }
+    }
+
+    fn mouse_input(&mut self, ui: &Ui, radius: f32, is_active: bool) -> bool {
+        let delta = ui.mouse_drag_delta_with_threshold(MouseButton::Left, 0.0001);
+        let movement = match self.drag_mode {
+            DragMode::Vertical => -delta[1],
+            DragMode::Horizontal => delta[0],
+            DragMode::VerticalHorizontal => delta[0] - delta[1],
+            DragMode::Rotary => 0.0,
+        };
+
+        let step = self.scale(ui.io()) / self.current_sensitivity().speed;
+
+        let mut value_changed = false;
+        if is_active && self.drag_mode == DragMode::Rotary {
+            let center = [
+                ui.item_rect_min()[0] + radius,
+                ui.item_rect_min()[1] + radius,
+            ];
+            let (angle_min, angle_max) = self.sweep();
+            if let Some(t) =
+                rotary_position(ui.io().mouse_pos, center, radius, angle_min, angle_max)
+            {
+                value_changed = self.set_t(t);
+            }
+        } else if is_active && movement != 0.0 {
+            let t = self.t() + movement * step;
+            value_changed = self.set_t(t);
+
+            //When stepped, keep accumulating the drag until the next step is reached so slow
+            //drags still advance. Past the ends of the range the excess is dropped.
+            if self.effective_steps().is_continuous() || value_changed || t <= 0.0 || t >= 1.0 {
+                //There may be a way to do this without using this
+                ui.reset_mouse_drag_delta(MouseButton::Left);
+            }
+        }
+        value_changed
+    }
+
+    /// Keys only adjust the knob while it's activated, the gamepad stick whenever it's focused.
+    fn keyboard_input(&mut self, ui: &Ui, state: &KnobState, adjusting: bool) -> bool {
+        let io = ui.io();
+        let mut value_changed = false;
+
+        if adjusting {
+            if ui.is_key_pressed(Key::Home) {
+                value_changed |= self.set_t(0.0);
+            }
+            if ui.is_key_pressed(Key::End) {
+                value_changed |= self.set_t(1.0);
+            }
+
+            let mut count = 0.0;
+            if ui.is_key_pressed(Key::UpArrow) || ui.is_key_pressed(Key::RightArrow) {
+                count += 1.0;
+            }
+            if ui.is_key_pressed(Key::DownArrow) || ui.is_key_pressed(Key::LeftArrow) {
+                count -= 1.0;
+            }
+            if ui.is_key_pressed(Key::PageUp) {
+                count += 10.0;
+            }
+            if ui.is_key_pressed(Key::PageDown) {
+                count -= 10.0;
+            }
+            if count != 0.0 {
+                let t_step = self.key_step * self.scale(io);
+                value_changed |= self.offset(count, t_step);
+            }
+        }
+
+        //Gamepad, the d-pad is left to imgui's navigation
+        let stick = io[NavInput::LStickUp] - io[NavInput::LStickDown] + io[NavInput::LStickRight]
+            - io[NavInput::LStickLeft];
+        if stick != 0.0 {
+            let tweak = if io[NavInput::TweakSlow] > 0.0 {
+                0.1
+            } else if io[NavInput::TweakFast] > 0.0 {
+                10.0
+            } else {
+                1.0
+            };
+            if self.effective_steps().is_continuous() {
+                let t = self.t() + stick * tweak * io.delta_time * 0.5;
+                value_changed |= self.set_t(t);
+            } else {
+                let accumulated =
+                    state.get_f32(Slot::Accumulator) + stick * tweak * io.delta_time * 4.0;
+                let whole = accumulated.trunc();
+                state.set_f32(Slot::Accumulator, accumulated - whole);
+                if whole != 0.0 {
+                    value_changed |= self.offset(whole, 0.0);
+                }
+            }
+        }
+
+        value_changed
+    }
+
+    /// Text input drawn in place of the drag widget, or over the knob without one. Closed on
+    /// Enter, Escape or when it loses focus.
+    fn text_input(&mut self, ui: &Ui, state: &KnobState, radius: f32) -> bool {
+        let cursor_pos = ui.cursor_screen_pos();
+        if !self.has_drag() {
+            ui.set_item_allow_overlap();
+            let rect_min = ui.item_rect_min();
+            ui.set_cursor_screen_pos([rect_min[0], rect_min[1] + radius - ui.frame_height() * 0.5]);
+        }
+        ui.set_next_item_width(radius * 2.0);
+        if state.get_bool(Slot::EditFocus) {
+            ui.set_keyboard_focus_here(FocusedWidget::Next);
+            state.set_bool(Slot::EditFocus, false);
+        }
+
+        let mut text = ImString::with_capacity(64);
+        text.push_str(&self.value_text());
+        let entered = ui
+            .input_text(
+                &ImString::new(format!("##{}_KNOB_INPUT_", self.label.to_str())),
+                &mut text,
+            )
+            .enter_returns_true(true)
+            .auto_select_all(true)
+            .build();
+        let closed = ui.is_item_deactivated();
+
+        let value_changed = entered && self.set_text(text.to_str());
+        if entered || closed {
+            state.set_bool(Slot::Editing, false);
+        }
+
+        if !self.has_drag() {
+            ui.set_cursor_screen_pos(cursor_pos);
+        }
+        value_changed
+    }
+
+    /// Drag widget below the knob. Its result is snapped to the knob's steps, with the part
+    /// lost to snapping kept until the drag ends so slow drags still advance.
+    fn drag_input(&mut self, ui: &Ui, control: &mut ControlState) {
+        let before = self.value();
+        let format = match (self.formatter, &self.drag_format) {
+            //Drag only takes printf formats, so the text is passed with `%` escaped
+            (Some(formatter), _) => ImString::new(formatter.format(before).replace('%', "%%")),
+            (None, Some(format)) => format.to_owned(),
+            (None, None) => ImString::new("%.3f"),
+        };
+        let state = KnobState::new(ui, self.label);
+        let mut drag_value = *self.p_value;
+        let (v_min, v_max) = self.bounds();
+        let dragged = Drag::new(&ImString::new(format!(
+            "###{}_KNOB_DRAG_CONTORL_",
+            self.label.to_str()
+        )))
+        .range(self.v_min..=self.v_max)
+        .display_format(&format)
+        //Imgui's own text entry would read the formatted text back with scanf
+        .flags(SliderFlags::NO_INPUT)
+        .speed((self.taper.slope(before, v_min, v_max) / 1000.0) as f32)
+        .build(ui, &mut drag_value);
+
+        let is_active = ui.is_item_active();
+        let mut remainder = if state.get_bool(Slot::Dragging) {
+            state.get_f64(Slot::DragRemainderLow, Slot::DragRemainderHigh)
+        } else {
+            0.0
+        };
+        let mut changed = false;
+        if dragged {
+            let value = self.limit(drag_value.to_f64() + remainder);
+            let snapped = self
+                .effective_steps()
+                .snap(value, v_min, v_max, &self.taper);
+            changed = self.set_value(snapped);
+            remainder = value - self.value();
+        }
+        if !is_active {
+            remainder = 0.0;
+        }
+        state.set_bool(Slot::Dragging, is_active);
+        state.set_f64(Slot::DragRemainderLow, Slot::DragRemainderHigh, remainder);
+
+        let io = ui.io();
+        if self.keyboard
+            && ((ui.is_item_clicked(MouseButton::Left) && io.key_ctrl)
+                || (ui.is_item_hovered() && ui.is_mouse_double_clicked(MouseButton::Left)))
+        {
+            state.set_bool(Slot::Editing, true);
+            state.set_bool(Slot::EditFocus, true);
+        }
+
+        //Edits made with the drag widget are reported as gestures of the knob
+        let finished = ui.is_item_deactivated();
+        let mut started = ui.is_item_activated();
+        if changed && !is_active && !finished {
+            started = true;
+            control.edit_finished = true;
+        }
+        if started {
+            state.set_f64(Slot::EditStartLow, Slot::EditStartHigh, before);
+            state.set_bool(Slot::HasEditStart, true);
+            control.edit_started = true;
+            control.edit_start = before;
+        }
+        control.edit_finished |= finished;
+        control.value_changed |= changed;
+        control.delta += self.value() - before;
+    }
+
+    pub fn build(mut self, ui: &'a Ui) -> Knob<'a, T> {
+        let radius = self.radius_or_default(ui);
+        let width = radius * 2.0;
+        let has_drag = self.has_drag();
+        let has_widgets = self.title.is_some() || has_drag;
+        let item_width = if has_widgets {
+            Some(ui.push_item_width(width))
+        } else {
+            None
+        };
+
+        if let Some(title) = &self.title {
+            knob_title(ui, title, width);
+        }
+
+        self.load_state(ui);
+        let (angle_min, angle_max) = self.sweep();
+        let t = self.t();
+        let angle = angle_min + (angle_max - angle_min) * t;
+        let origin_t = self.origin_t();
+        let origin_angle = angle_min + (angle_max - angle_min) * origin_t;
+        let screen_pos = ui.cursor_screen_pos();
+        let mut control = if self.controllable {
+            self.control(ui, radius)
+        } else {
+            ControlState {
+                value_changed: false,
+                editing: false,
+                was_reset: false,
+                edit_started: false,
+                edit_finished: false,
+                edit_start: self.value(),
+                delta: 0.0,
+                is_active: ui.is_item_active(),
+                is_hovered: ui.is_item_hovered(),
+                is_focused: ui.is_item_focused(),
+            }
+        };
+
+        if self.tooltip && (control.is_hovered || control.is_active) && !control.editing {
+            let text = ImString::new(self.value_text());
+            ui.tooltip(|| {
+                if let Some(description) = self.description {
+                    ui.text(description);
+                    ui.separator();
+                }
+                ui.text(&text);
+            });
+        }
+
+        if has_drag && !control.editing {
+            self.drag_input(ui, &mut control);
+        }
+
+        let label = self.label;
+        let p_value = self.p_value;
+        let v_min = self.v_min;
+        let v_max = self.v_max;
+        let v_default = self.v_default.unwrap_or(v_min);
+        let taper = self.taper;
+        let steps = self.steps;
+
+        let knob = Knob {
+            ui,
+            label,
+            p_value,
+            v_min,
+            v_max,
+            v_default,
+            radius,
+            taper,
+            steps,
+            screen_pos,
+            value_changed: control.value_changed,
+            was_reset: control.was_reset,
+            edit_started: control.edit_started,
+            edit_finished: control.edit_finished,
+            edit_start_value: T::from_f64(control.edit_start),
+            center: [screen_pos[0] + radius, screen_pos[1] + radius],
+            draw_list: ui.get_window_draw_list(),
+            is_active: control.is_active,
+            is_hovered: control.is_hovered,
+            is_focused: control.is_focused,
+            angle_min,
+            angle_max,
+            wiper_origin: self.wiper_origin,
+            encoder: self.encoder,
+            delta: control.delta,
+            origin_t,
+            origin_angle,
+            t,
+            angle,
+            angle_cos: angle.cos(),
+            angle_sin: angle.sin(),
+        };
+
+        if let Some(item_width) = item_width {
+            item_width.pop(ui);
+        }
+
+        let focus_ring = self.focus_ring && knob.is_focused && ui.io().nav_visible;
+        if self.style.is_some() || self.layers.is_some() || focus_ring {
+            let theme = current_knob_theme(ui);
+            let colors = self.colors.unwrap_or(theme.colors);
+            if let Some(style) = self.style {
+                style.draw(&knob, &colors);
+            }
+            if let Some(layers) = self.layers {
+                draw_layers(&knob, layers, &colors);
+            }
+            if focus_ring {
+                knob.draw_focus_ring(&theme.focus);
+            }
+        }
+
+        knob
+    }
+}
+
+struct ControlState {
+    value_changed: bool,
+    /// The text input was shown this frame.
+    editing: bool,
+    was_reset: bool,
+    edit_started: bool,
+    edit_finished: bool,
+    edit_start: f64,
+    delta: f64,
+    is_active: bool,
+    is_hovered: bool,
+    is_focused: bool,
+}
+
+/// Pressed this frame, ignoring key repeat.
+fn key_pressed_once(key: Key) -> bool {
+    unsafe { sys::igIsKeyPressed(sys::igGetKeyIndex(key as i32), false) }
+}
+
+/// Knob position under the pointer for `DragMode::Rotary`. Pointers in the gap below the
+/// sweep snap to the nearest end, pointers too close to the center are ignored.
+fn rotary_position(
+    mouse_pos: [f32; 2],
+    center: [f32; 2],
+    radius: f32,
+    angle_min: f32,
+    angle_max: f32,
+) -> Option<f32> {
+    let dx = mouse_pos[0] - center[0];
+    let dy = mouse_pos[1] - center[1];
+    if (dx * dx + dy * dy).sqrt() < radius * 0.1 {
+        return None;
+    }
+    let direction = if angle_max < angle_min { -1.0 } else { 1.0 };
+    let sweep = (angle_max - angle_min).abs();
+    let rel = ((dy.atan2(dx) - angle_min) * direction).rem_euclid(PI * 2.0);
+    Some(if rel <= sweep {
+        rel / sweep
+    } else if rel - sweep < PI * 2.0 - rel {
+        1.0
+    } else {
+        0.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(angle: f32) -> [f32; 2] {
+        [100.0 + angle.cos() * 20.0, 100.0 + angle.sin() * 20.0]
+    }
+
+    #[test]
+    fn rotary() {
+        let center = [100.0, 100.0];
+        let (angle_min, angle_max) = (PI * 0.75, PI * 2.25);
+        let position = |angle: f32| rotary_position(at(angle), center, 20.0, angle_min, angle_max);
+        assert!(position(PI * 0.75).unwrap().abs() < 1e-5);
+        assert!((position(PI * 1.5).unwrap() - 0.5).abs() < 1e-5);
+        assert!((position(PI * 0.25).unwrap() - 1.0).abs() < 1e-5);
+        //In the gap below the sweep the nearest end wins
+        assert_eq!(position(PI * 0.6), Some(0.0));
+        assert_eq!(position(PI * 0.4), Some(1.0));
+        assert_eq!(
+            rotary_position(center, center, 20.0, angle_min, angle_max),
+            None
+        );
+    }
+
+    #[test]
+    fn rotary_reversed() {
+        let center = [100.0, 100.0];
+        let position = |angle: f32| rotary_position(at(angle), center, 20.0, PI * 2.25, PI * 0.75);
+        assert!(position(PI * 0.25).unwrap().abs() < 1e-5);
+        assert!((position(PI * 1.5).unwrap() - 0.5).abs() < 1e-5);
+    }
+}
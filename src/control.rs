@@ -0,0 +1,237 @@
+use imgui::{Io, Ui};
+use std::sync::RwLock;
+
+/// A set of modifier keys, held when any one of them is down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub super_: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        ctrl: false,
+        shift: false,
+        alt: false,
+        super_: false,
+    };
+    pub const CTRL: Modifiers = Modifiers {
+        ctrl: true,
+        ..Modifiers::NONE
+    };
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ..Modifiers::NONE
+    };
+    pub const ALT: Modifiers = Modifiers {
+        alt: true,
+        ..Modifiers::NONE
+    };
+    pub const SUPER: Modifiers = Modifiers {
+        super_: true,
+        ..Modifiers::NONE
+    };
+    pub const ANY: Modifiers = Modifiers {
+        ctrl: true,
+        shift: true,
+        alt: true,
+        super_: true,
+    };
+
+    pub fn or(self, other: Modifiers) -> Modifiers {
+        Modifiers {
+            ctrl: self.ctrl || other.ctrl,
+            shift: self.shift || other.shift,
+            alt: self.alt || other.alt,
+            super_: self.super_ || other.super_,
+        }
+    }
+
+    pub fn is_held(&self, io: &Io) -> bool {
+        (self.ctrl && io.key_ctrl)
+            || (self.shift && io.key_shift)
+            || (self.alt && io.key_alt)
+            || (self.super_ && io.key_super)
+    }
+}
+
+/// How far a knob moves for a given mouse drag.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sensitivity {
+    /// Pixels of mouse movement for a full sweep of the knob.
+    pub speed: f32,
+    pub fine_modifier: Modifiers,
+    /// Knob movement is divided by this while `fine_modifier` is held.
+    pub fine_divisor: f32,
+    pub coarse_modifier: Modifiers,
+    /// Knob movement is multiplied by this while `coarse_modifier` is held.
+    pub coarse_multiplier: f32,
+}
+
+impl Sensitivity {
+    /// Built in settings, 200 pixels for a full sweep and 10 times finer with Shift or Alt.
+    pub const fn new() -> Sensitivity {
+        Sensitivity {
+            speed: 200.0,
+            fine_modifier: Modifiers {
+                shift: true,
+                alt: true,
+                ..Modifiers::NONE
+            },
+            fine_divisor: 10.0,
+            coarse_modifier: Modifiers::NONE,
+            coarse_multiplier: 4.0,
+        }
+    }
+
+    pub fn is_fine(&self, io: &Io) -> bool {
+        self.fine_modifier.is_held(io)
+    }
+
+    /// Multiplier for knob movement from the currently held modifiers.
+    pub fn scale(&self, io: &Io) -> f32 {
+        if self.is_fine(io) {
+            1.0 / self.fine_divisor
+        } else if self.coarse_modifier.is_held(io) {
+            self.coarse_multiplier
+        } else {
+            1.0
+        }
+    }
+
+    /// Normalized knob travel for one pixel of mouse movement with the currently held modifiers.
+    pub fn step(&self, io: &Io) -> f32 {
+        self.scale(io) / self.speed
+    }
+}
+
+impl Default for Sensitivity {
+    fn default() -> Sensitivity {
+        default_sensitivity()
+    }
+}
+
+static DEFAULT_SENSITIVITY: RwLock<Sensitivity> = RwLock::new(Sensitivity::new());
+
+/// Sets the sensitivity used by every knob that doesn't set its own.
+pub fn set_default_sensitivity(sensitivity: Sensitivity) {
+    *DEFAULT_SENSITIVITY.write().unwrap() = sensitivity;
+}
+
+pub fn default_sensitivity() -> Sensitivity {
+    *DEFAULT_SENSITIVITY.read().unwrap()
+}
+
+/// Mouse gesture used to turn a knob.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DragMode {
+    /// Dragging up increases the value.
+    #[default]
+    Vertical,
+    /// Dragging right increases the value.
+    Horizontal,
+    /// Dragging up or right increases the value.
+    VerticalHorizontal,
+    /// The knob follows the angle of the pointer around the knob center.
+    Rotary,
+}
+
+/// When hovering a knob and scrolling the mouse wheel turns it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum WheelMode {
+    Disabled,
+    Enabled,
+    /// Only when the knob's window has nothing to scroll vertically, so knobs inside
+    /// scrollable windows don't steal scrolling.
+    #[default]
+    Auto,
+    /// Only while one of the modifiers is held.
+    WithModifier(Modifiers),
+}
+
+/// Gestures that return a knob to its default value, combined with `or`. Defaults to
+/// double-click, `ResetGestures::NONE` disables reset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResetGestures {
+    /// Only without modifiers held when the knob's text input is enabled.
+    pub double_click: bool,
+    /// Takes the place of Ctrl+click opening the text input.
+    pub ctrl_click: bool,
+    pub alt_click: bool,
+    /// A "Reset" entry in a menu opened by right-clicking the knob, see also
+    /// `KnobBuilder::context_menu`.
+    pub context_menu: bool,
+}
+
+impl ResetGestures {
+    pub const NONE: ResetGestures = ResetGestures {
+        double_click: false,
+        ctrl_click: false,
+        alt_click: false,
+        context_menu: false,
+    };
+    pub const DOUBLE_CLICK: ResetGestures = ResetGestures {
+        double_click: true,
+        ..ResetGestures::NONE
+    };
+    pub const CTRL_CLICK: ResetGestures = ResetGestures {
+        ctrl_click: true,
+        ..ResetGestures::NONE
+    };
+    pub const ALT_CLICK: ResetGestures = ResetGestures {
+        alt_click: true,
+        ..ResetGestures::NONE
+    };
+    pub const CONTEXT_MENU: ResetGestures = ResetGestures {
+        context_menu: true,
+        ..ResetGestures::NONE
+    };
+
+    pub fn or(self, other: ResetGestures) -> ResetGestures {
+        ResetGestures {
+            double_click: self.double_click || other.double_click,
+            ctrl_click: self.ctrl_click || other.ctrl_click,
+            alt_click: self.alt_click || other.alt_click,
+            context_menu: self.context_menu || other.context_menu,
+        }
+    }
+}
+
+impl Default for ResetGestures {
+    fn default() -> ResetGestures {
+        ResetGestures::DOUBLE_CLICK
+    }
+}
+
+/// How a knob maps its movement to the value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum EncoderMode {
+    /// The value stops at the ends of the range.
+    #[default]
+    Absolute,
+    /// The value wraps from `v_max` back to `v_min`, for cyclic parameters such as phase
+    /// or hue. `v_max` and `v_min` share the same position on a full 360 degree sweep.
+    Endless,
+    /// Endless, but the value is set to the change made this frame instead of the knob's
+    /// position, which is kept by the knob itself.
+    Relative,
+}
+
+impl EncoderMode {
+    pub fn is_endless(&self) -> bool {
+        *self != EncoderMode::Absolute
+    }
+}
+
+impl WheelMode {
+    pub fn is_enabled(&self, ui: &Ui) -> bool {
+        match *self {
+            WheelMode::Disabled => false,
+            WheelMode::Enabled => true,
+            WheelMode::Auto => ui.scroll_max_y() <= 0.0,
+            WheelMode::WithModifier(modifiers) => modifiers.is_held(ui.io()),
+        }
+    }
+}
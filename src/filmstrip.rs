@@ -0,0 +1,81 @@
+use crate::*;
+
+/// Direction the frames of a filmstrip are laid out in the texture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FilmstripOrientation {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+/// Pre-rendered knob frames stacked in one texture, the first frame at `v_min` and the last
+/// at `v_max`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Filmstrip {
+    pub texture: TextureId,
+    pub frames: u32,
+    pub orientation: FilmstripOrientation,
+}
+
+impl Filmstrip {
+    pub fn new(texture: TextureId, frames: u32, orientation: FilmstripOrientation) -> Filmstrip {
+        Filmstrip {
+            texture,
+            frames,
+            orientation,
+        }
+    }
+
+    /// Frame shown for the normalized knob position `t`.
+    pub fn frame(&self, t: f32) -> u32 {
+        let last = self.frames.max(1) - 1;
+        ((t.clamp(0.0, 1.0) * last as f32).round() as u32).min(last)
+    }
+
+    /// Texture coordinates of a frame.
+    pub fn frame_uv(&self, frame: u32) -> ([f32; 2], [f32; 2]) {
+        let frames = self.frames.max(1) as f32;
+        let start = frame as f32 / frames;
+        let end = (frame + 1) as f32 / frames;
+        match self.orientation {
+            FilmstripOrientation::Vertical => ([0.0, start], [1.0, end]),
+            FilmstripOrientation::Horizontal => ([start, 0.0], [end, 1.0]),
+        }
+    }
+
+    /// Draws the frame for the knob's position over the knob's square, tinted by `color`.
+    pub fn draw<T: KnobValue>(&self, knob: &Knob<T>, color: &ColorSet) {
+        let (uv_min, uv_max) = self.frame_uv(self.frame(knob.t));
+        knob.draw_image(self.texture, 1.0, uv_min, uv_max, color);
+    }
+}
+
+/// Draws a knob from a filmstrip, use a white `tint` to draw the frames unchanged.
+pub fn draw_filmstrip_knob<T: KnobValue>(knob: &Knob<T>, filmstrip: &Filmstrip, tint: &ColorSet) {
+    filmstrip.draw(knob, tint);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame() {
+        let filmstrip = Filmstrip::new(TextureId::from(0), 64, FilmstripOrientation::Vertical);
+        assert_eq!(filmstrip.frame(0.0), 0);
+        assert_eq!(filmstrip.frame(0.5), 32);
+        assert_eq!(filmstrip.frame(1.0), 63);
+        assert_eq!(filmstrip.frame(-1.0), 0);
+        assert_eq!(filmstrip.frame(2.0), 63);
+        let single = Filmstrip::new(TextureId::from(0), 0, FilmstripOrientation::Vertical);
+        assert_eq!(single.frame(1.0), 0);
+    }
+
+    #[test]
+    fn frame_uv() {
+        let vertical = Filmstrip::new(TextureId::from(0), 4, FilmstripOrientation::Vertical);
+        assert_eq!(vertical.frame_uv(1), ([0.0, 0.25], [1.0, 0.5]));
+        let horizontal = Filmstrip::new(TextureId::from(0), 4, FilmstripOrientation::Horizontal);
+        assert_eq!(horizontal.frame_uv(3), ([0.75, 0.0], [1.0, 1.0]));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::*;
+
+/// A knob drawn from a cap texture rotated to the knob's angle, with optional static
+/// textures drawn below and above it. The cap is drawn with its pointer facing up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageKnob {
+    pub cap: TextureId,
+    /// Half the width of the cap relative to the knob radius.
+    pub cap_size: f32,
+    pub background: Option<TextureId>,
+    /// Drawn over the cap, such as a fixed highlight or shadow.
+    pub foreground: Option<TextureId>,
+    /// Tints of the textures, white by default so they're drawn unchanged.
+    pub background_tint: LayerColor,
+    pub cap_tint: LayerColor,
+    pub foreground_tint: LayerColor,
+}
+
+impl ImageKnob {
+    pub fn new(cap: TextureId) -> ImageKnob {
+        ImageKnob {
+            cap,
+            cap_size: 1.0,
+            background: None,
+            foreground: None,
+            background_tint: LayerColor::Fixed(WHITE),
+            cap_tint: LayerColor::Fixed(WHITE),
+            foreground_tint: LayerColor::Fixed(WHITE),
+        }
+    }
+
+    pub fn layers(&self) -> Vec<KnobLayer> {
+        let mut layers = Vec::new();
+        if let Some(texture) = self.background {
+            layers.push(KnobLayer::Image {
+                texture,
+                size: 1.0,
+                color: self.background_tint,
+            });
+        }
+        layers.push(KnobLayer::RotatedImage {
+            texture: self.cap,
+            size: self.cap_size,
+            angle: LayerAngle::Value,
+            color: self.cap_tint,
+        });
+        if let Some(texture) = self.foreground {
+            layers.push(KnobLayer::Image {
+                texture,
+                size: 1.0,
+                color: self.foreground_tint,
+            });
+        }
+        layers
+    }
+
+    /// `colors` only apply to tints set to one of the color roles.
+    pub fn draw<T: KnobValue>(&self, knob: &Knob<T>, colors: &KnobColors) {
+        draw_layers(knob, &self.layers(), colors);
+    }
+}
+
+/// Draws a rotated image knob, tints set to a color role are drawn white.
+pub fn draw_image_knob<T: KnobValue>(knob: &Knob<T>, image_knob: &ImageKnob) {
+    image_knob.draw(knob, &KnobColors::new(WHITE, WHITE, WHITE));
+}
+
+const WHITE: ColorSet = ColorSet {
+    base: [1.0, 1.0, 1.0, 1.0],
+    hovered: [1.0, 1.0, 1.0, 1.0],
+    active: [1.0, 1.0, 1.0, 1.0],
+};
@@ -0,0 +1,242 @@
+use crate::*;
+
+/// Color of a layer, one of the `KnobColors` roles or a fixed set of colors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayerColor {
+    Base,
+    Highlight,
+    Lowlight,
+    Fixed(ColorSet),
+}
+
+impl LayerColor {
+    pub fn resolve<'c>(&'c self, colors: &'c KnobColors) -> &'c ColorSet {
+        match self {
+            LayerColor::Base => &colors.base,
+            LayerColor::Highlight => &colors.highlight,
+            LayerColor::Lowlight => &colors.lowlight,
+            LayerColor::Fixed(color) => color,
+        }
+    }
+}
+
+/// Angle a layer is drawn at, bound to the knob's value or sweep.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayerAngle {
+    Min,
+    Max,
+    Value,
+    /// The wiper origin.
+    Origin,
+    Fixed(f32),
+}
+
+impl LayerAngle {
+    pub fn resolve<T: KnobValue>(&self, knob: &Knob<T>) -> f32 {
+        match *self {
+            LayerAngle::Min => knob.angle_min,
+            LayerAngle::Max => knob.angle_max,
+            LayerAngle::Value => knob.angle,
+            LayerAngle::Origin => knob.origin_angle,
+            LayerAngle::Fixed(angle) => angle,
+        }
+    }
+}
+
+/// One element of a knob drawing. Sizes and radii are relative to the knob radius, as for
+/// the `Knob::draw_*` functions. A list of layers drawn in order makes up a knob design,
+/// `KnobStyle::layers` gives the built in ones.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KnobLayer {
+    /// `shrink` is taken off the size over the sweep.
+    Circle {
+        size: f32,
+        shrink: f32,
+        filled: bool,
+        segments: u32,
+        color: LayerColor,
+    },
+    /// Arc between two angles with `offset` added to both. With `hide_at_origin` the arc
+    /// isn't drawn while the value sits at the wiper origin.
+    Arc {
+        radius: f32,
+        size: f32,
+        start: LayerAngle,
+        end: LayerAngle,
+        offset: f32,
+        segments: u32,
+        cap: ArcCap,
+        hide_at_origin: bool,
+        color: LayerColor,
+    },
+    Dot {
+        size: f32,
+        radius: f32,
+        angle: LayerAngle,
+        filled: bool,
+        segments: u32,
+        color: LayerColor,
+    },
+    Tick {
+        start: f32,
+        end: f32,
+        width: f32,
+        angle: LayerAngle,
+        color: LayerColor,
+    },
+    /// A tick for each step spread over the sweep, the one nearest the value drawn in
+    /// `selected_color`.
+    StepTicks {
+        steps: u32,
+        start: f32,
+        end: f32,
+        width: f32,
+        color: LayerColor,
+        selected_color: LayerColor,
+    },
+    Label {
+        text: ImString,
+        radius: f32,
+        angle: LayerAngle,
+        color: LayerColor,
+    },
+    /// Texture drawn centered on the knob, `size` is half its width.
+    Image {
+        texture: TextureId,
+        size: f32,
+        color: LayerColor,
+    },
+    /// Texture centered on the knob and rotated so its top faces `angle`.
+    RotatedImage {
+        texture: TextureId,
+        size: f32,
+        angle: LayerAngle,
+        color: LayerColor,
+    },
+    /// Frame of a filmstrip picked by the knob position, drawn over the knob's square.
+    Filmstrip {
+        filmstrip: Filmstrip,
+        color: LayerColor,
+    },
+}
+
+impl KnobLayer {
+    pub fn draw<T: KnobValue>(&self, knob: &Knob<T>, colors: &KnobColors) {
+        match self {
+            KnobLayer::Circle {
+                size,
+                shrink,
+                filled,
+                segments,
+                color,
+            } => knob.draw_circle(
+                size - knob.t * shrink,
+                color.resolve(colors),
+                *filled,
+                *segments,
+            ),
+            KnobLayer::Arc {
+                radius,
+                size,
+                start,
+                end,
+                offset,
+                segments,
+                cap,
+                hide_at_origin,
+                color,
+            } => {
+                if !hide_at_origin || (knob.t - knob.origin_t).abs() > 0.01 {
+                    knob.draw_arc_with_cap(
+                        *radius,
+                        *size,
+                        start.resolve(knob) + offset,
+                        end.resolve(knob) + offset,
+                        color.resolve(colors),
+                        *segments,
+                        *cap,
+                    );
+                }
+            }
+            KnobLayer::Dot {
+                size,
+                radius,
+                angle,
+                filled,
+                segments,
+                color,
+            } => knob.draw_dot(
+                *size,
+                *radius,
+                angle.resolve(knob),
+                color.resolve(colors),
+                *filled,
+                *segments,
+            ),
+            KnobLayer::Tick {
+                start,
+                end,
+                width,
+                angle,
+                color,
+            } => knob.draw_tick(
+                *start,
+                *end,
+                *width,
+                angle.resolve(knob),
+                color.resolve(colors),
+            ),
+            KnobLayer::StepTicks {
+                steps,
+                start,
+                end,
+                width,
+                color,
+                selected_color,
+            } => {
+                let last = (*steps).max(2) - 1;
+                let selected = (knob.t * last as f32).round() as u32;
+                for n in 0..=last {
+                    let a = n as f32 / last as f32;
+                    let angle = knob.angle_min + (knob.angle_max - knob.angle_min) * a;
+                    let color = if n == selected { selected_color } else { color };
+                    knob.draw_tick(*start, *end, *width, angle, color.resolve(colors));
+                }
+            }
+            KnobLayer::Label {
+                text,
+                radius,
+                angle,
+                color,
+            } => knob.draw_text(text, *radius, angle.resolve(knob), color.resolve(colors)),
+            KnobLayer::Image {
+                texture,
+                size,
+                color,
+            } => knob.draw_image(
+                *texture,
+                *size,
+                [0.0, 0.0],
+                [1.0, 1.0],
+                color.resolve(colors),
+            ),
+            KnobLayer::RotatedImage {
+                texture,
+                size,
+                angle,
+                color,
+            } => {
+                knob.draw_rotated_image(*texture, *size, angle.resolve(knob), color.resolve(colors))
+            }
+            KnobLayer::Filmstrip { filmstrip, color } => {
+                filmstrip.draw(knob, color.resolve(colors))
+            }
+        }
+    }
+}
+
+pub fn draw_layers<T: KnobValue>(knob: &Knob<T>, layers: &[KnobLayer], colors: &KnobColors) {
+    for layer in layers {
+        layer.draw(knob, colors);
+    }
+}
@@ -1,8 +1,36 @@
-//TODO stepped increments
 //TODO make something similar to drag control but with single click (doesn't need to support drag)
 
+#![allow(clippy::too_many_arguments)]
+
 use imgui::*;
 use std::f32::consts::PI;
+
+mod arc;
+mod builder;
+mod control;
+mod filmstrip;
+mod image_knob;
+mod layers;
+mod selector;
+mod state;
+mod style;
+mod taper;
+mod theme;
+mod units;
+mod value;
+pub use arc::*;
+pub use builder::*;
+pub use control::*;
+pub use filmstrip::*;
+pub use image_knob::*;
+pub use layers::*;
+pub use selector::*;
+pub use style::*;
+pub use taper::*;
+pub use theme::*;
+pub use units::*;
+pub use value::*;
+
 pub fn bezier_arc(center: [f32; 2], start: [f32; 2], end: [f32; 2]) -> ([f32; 2], [f32; 2]) {
     let ax = start[0] - center[0];
     let ay = start[1] - center[1];
@@ -47,6 +75,9 @@ pub fn draw_arc1(
         .build();
 }
 
+/// Draws an arc as joined bezier curves into `draw_list`. The joins show through
+/// transparent colors, `draw_arc_strip` draws a single anti-aliased strip into the current
+/// window's draw list.
 pub fn draw_arc(
     draw_list: &DrawListMut,
     center: [f32; 2],
@@ -89,53 +120,24 @@ pub fn draw_arc(
     );
 }
 
-pub fn knob_control(
+pub fn knob_control<T: KnobValue>(
     ui: &Ui,
     id: &ImStr,
-    p_value: &mut f32,
-    v_min: f32,
-    v_max: f32,
-    v_default: f32,
+    p_value: &mut T,
+    v_min: T,
+    v_max: T,
+    v_default: T,
     radius: f32,
 ) -> bool {
-    ui.invisible_button(id, [radius * 2.0, radius * 2.0]);
-
-    let mut value_changed = false;
-
-    let is_active = ui.is_item_active();
-    let delta = ui.mouse_drag_delta_with_threshold(MouseButton::Left, 0.0001);
-
-    let io = ui.io();
-    io.key_shift;
-
-    //Maybe this should be configurable
-    let speed = if io.key_shift || io.key_alt {
-        2000.0
-    } else {
-        200.0
-    };
-
-    if ui.is_mouse_double_clicked(MouseButton::Left) && is_active {
-        *p_value = v_default;
-        value_changed = true;
-    } else if is_active && delta[1] != 0.0 {
-        let step = (v_max - v_min) / speed;
-        *p_value -= delta[1] * step;
-        if *p_value < v_min {
-            *p_value = v_min;
-        }
-        if *p_value > v_max {
-            *p_value = v_max;
-        }
-        value_changed = true;
-
-        //There may be a way to do this without using this
-        ui.reset_mouse_drag_delta(MouseButton::Left);
-    }
-
-    value_changed
+    Knob::builder(id, p_value)
+        .range(v_min, v_max)
+        .default(v_default)
+        .radius(radius)
+        .build_control(ui)
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorSet {
     pub base: [f32; 4],
     pub hovered: [f32; 4],
@@ -162,68 +164,99 @@ impl From<[f32; 4]> for ColorSet {
     }
 }
 
-pub struct Knob<'a> {
+/// Where wiper arcs start from. Bipolar parameters such as pan use `Center` so the
+/// wiper grows left or right from the middle of the sweep.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum WiperOrigin {
+    #[default]
+    Min,
+    Center,
+    Max,
+    /// The knob's `v_default`.
+    Default,
+    Value(f64),
+}
+
+pub struct Knob<'a, T: KnobValue = f32> {
     pub ui: &'a Ui<'a>,
     pub label: &'a ImStr,
-    pub p_value: &'a mut f32,
-    pub v_min: f32,
-    pub v_max: f32,
-    pub v_default: f32,
+    pub p_value: &'a mut T,
+    pub v_min: T,
+    pub v_max: T,
+    pub v_default: T,
     pub radius: f32,
+    pub taper: Taper<'a>,
+    pub steps: Steps,
     pub screen_pos: [f32; 2],
     pub value_changed: bool,
+    /// The value was returned to `v_default` by one of the reset gestures this frame.
+    pub was_reset: bool,
+    /// A gesture changing the value began this frame. Dragging starts a gesture when the
+    /// knob is clicked and finishes it on release, other edits start and finish in one frame.
+    pub edit_started: bool,
+    pub edit_finished: bool,
+    /// Value when the current or last gesture started, or the current value before any gesture.
+    pub edit_start_value: T,
     pub center: [f32; 2],
     pub draw_list: DrawListMut<'a>,
     pub is_active: bool,
     pub is_hovered: bool,
+    pub is_focused: bool,
     pub angle_min: f32,
     pub angle_max: f32,
+    pub wiper_origin: WiperOrigin,
+    pub encoder: EncoderMode,
+    /// Change made to the value this frame, taking the shortest way around for endless knobs.
+    pub delta: f64,
+    /// Normalized position and angle the wiper is drawn from.
+    pub origin_t: f32,
+    pub origin_angle: f32,
     pub t: f32,
     pub angle: f32,
     pub angle_cos: f32,
     pub angle_sin: f32,
 }
 
-impl<'a> Knob<'a> {
+impl<'a, T: KnobValue> Knob<'a, T> {
     pub fn new(
         ui: &'a Ui,
         label: &'a ImStr,
-        p_value: &'a mut f32,
-        v_min: f32,
-        v_max: f32,
-        v_default: f32,
+        p_value: &'a mut T,
+        v_min: T,
+        v_max: T,
+        v_default: T,
         radius: f32,
         controllable: bool,
-    ) -> Knob<'a> {
-        let angle_min = PI * 0.75;
-        let angle_max = PI * 2.25;
-        let t = (*p_value - v_min) / (v_max - v_min);
-        let angle = angle_min + (angle_max - angle_min) * t;
-        let screen_pos = ui.cursor_screen_pos();
-        let mut value_changed = false;
-        if controllable {
-            value_changed = knob_control(ui, label, p_value, v_min, v_max, v_default, radius);
-        }
-        Knob {
-            ui,
-            label,
-            p_value,
-            v_min,
-            v_max,
-            v_default,
-            radius,
-            screen_pos,
-            value_changed,
-            center: [screen_pos[0] + radius, screen_pos[1] + radius],
-            draw_list: ui.get_window_draw_list(),
-            is_active: ui.is_item_active(),
-            is_hovered: ui.is_item_hovered(),
-            angle_min,
-            angle_max,
-            t,
-            angle,
-            angle_cos: angle.cos(),
-            angle_sin: angle.sin(),
+    ) -> Knob<'a, T> {
+        Knob::builder(label, p_value)
+            .range(v_min, v_max)
+            .default(v_default)
+            .radius(radius)
+            .controllable(controllable)
+            .build(ui)
+    }
+
+    pub fn builder(label: &'a ImStr, p_value: &'a mut T) -> KnobBuilder<'a, T> {
+        KnobBuilder::new(label, p_value)
+    }
+
+    /// Number of discrete positions for stepped and integer knobs, `None` when continuous
+    /// or when there are more positions than fit in a `u32`.
+    pub fn step_count(&self) -> Option<u32> {
+        let range = (self.v_max.to_f64() - self.v_min.to_f64()).abs();
+        let positions = |intervals: f64| {
+            let count = intervals.round() + 1.0;
+            if count <= u32::MAX as f64 {
+                Some(count as u32)
+            } else {
+                None
+            }
+        };
+        match self.steps {
+            Steps::Count(n) if n >= 2 => Some(n),
+            Steps::Increment(inc) if inc > 0.0 => positions(range / inc),
+            _ if T::IS_INTEGER => positions(range),
+            _ => None,
         }
     }
 
@@ -258,6 +291,31 @@ impl<'a> Knob<'a> {
             .build();
     }
 
+    /// Draws text centered just outside the given radius, pushed outwards by half the text
+    /// size so labels around the knob don't overlap it.
+    pub fn draw_text(&self, text: &ImStr, radius: f32, angle: f32, color: &ColorSet) {
+        let size = self.ui.calc_text_size(text, false, -1.0);
+        let angle_cos = angle.cos();
+        let angle_sin = angle.sin();
+        let distance = radius * self.radius
+            + angle_cos.abs() * size[0] * 0.5
+            + angle_sin.abs() * size[1] * 0.5;
+        self.draw_list.add_text(
+            [
+                self.center[0] + angle_cos * distance - size[0] * 0.5,
+                self.center[1] + angle_sin * distance - size[1] * 0.5,
+            ],
+            if self.is_active {
+                color.active
+            } else if self.is_hovered {
+                color.hovered
+            } else {
+                color.base
+            },
+            text,
+        );
+    }
+
     pub fn draw_tick(&self, start: f32, end: f32, width: f32, angle: f32, color: &ColorSet) {
         let tick_start = start * self.radius;
         let tick_end = end * self.radius;
@@ -305,6 +363,80 @@ impl<'a> Knob<'a> {
             .build();
     }
 
+    /// Draws a texture centered on the knob, `size` is half its width relative to the radius.
+    pub fn draw_image(
+        &self,
+        texture: TextureId,
+        size: f32,
+        uv_min: [f32; 2],
+        uv_max: [f32; 2],
+        color: &ColorSet,
+    ) {
+        let half_size = size * self.radius;
+        let color = if self.is_active {
+            color.active
+        } else if self.is_hovered {
+            color.hovered
+        } else {
+            color.base
+        };
+        //The safe draw list has no image call in this imgui version, the window's draw list
+        //is the one `self.draw_list` refers to.
+        unsafe {
+            sys::ImDrawList_AddImage(
+                sys::igGetWindowDrawList(),
+                texture.id() as *mut _,
+                [self.center[0] - half_size, self.center[1] - half_size].into(),
+                [self.center[0] + half_size, self.center[1] + half_size].into(),
+                uv_min.into(),
+                uv_max.into(),
+                ImColor32::from(color).into(),
+            );
+        }
+    }
+
+    /// Draws a texture centered on the knob, rotated so its top faces `angle`.
+    pub fn draw_rotated_image(&self, texture: TextureId, size: f32, angle: f32, color: &ColorSet) {
+        let half_size = size * self.radius;
+        let rotation = angle + PI * 0.5;
+        let (sin, cos) = rotation.sin_cos();
+        let corner = |x: f32, y: f32| -> sys::ImVec2 {
+            [
+                self.center[0] + (x * cos - y * sin) * half_size,
+                self.center[1] + (x * sin + y * cos) * half_size,
+            ]
+            .into()
+        };
+        let color = if self.is_active {
+            color.active
+        } else if self.is_hovered {
+            color.hovered
+        } else {
+            color.base
+        };
+        unsafe {
+            sys::ImDrawList_AddImageQuad(
+                sys::igGetWindowDrawList(),
+                texture.id() as *mut _,
+                corner(-1.0, -1.0),
+                corner(1.0, -1.0),
+                corner(1.0, 1.0),
+                corner(-1.0, 1.0),
+                [0.0, 0.0].into(),
+                [1.0, 0.0].into(),
+                [1.0, 1.0].into(),
+                [0.0, 1.0].into(),
+                ImColor32::from(color).into(),
+            );
+        }
+    }
+
+    pub fn draw_focus_ring(&self, color: &ColorSet) {
+        self.draw_circle(1.0, color, false, 32);
+    }
+
+    /// Arc with butt caps, `bezier_count` is no longer used since arcs are drawn as a
+    /// single strip, see `draw_arc_with_cap`.
     pub fn draw_arc(
         &self,
         radius: f32,
@@ -313,12 +445,33 @@ impl<'a> Knob<'a> {
         end_angle: f32,
         color: &ColorSet,
         segments: u32,
-        bezier_count: u8,
+        _bezier_count: u8,
+    ) {
+        self.draw_arc_with_cap(
+            radius,
+            size,
+            start_angle,
+            end_angle,
+            color,
+            segments,
+            ArcCap::Butt,
+        );
+    }
+
+    /// Arc drawn with `draw_arc_strip`, with at least `segments` segments.
+    pub fn draw_arc_with_cap(
+        &self,
+        radius: f32,
+        size: f32,
+        start_angle: f32,
+        end_angle: f32,
+        color: &ColorSet,
+        segments: u32,
+        cap: ArcCap,
     ) {
         let track_radius = radius * self.radius;
-        let track_size = size * self.radius * 0.5 + 0.0001;
-        draw_arc(
-            &self.draw_list,
+        let track_size = size * self.radius * 0.5;
+        draw_arc_strip(
             self.center,
             track_radius,
             start_angle,
@@ -332,125 +485,133 @@ impl<'a> Knob<'a> {
                 color.base
             },
             segments,
-            bezier_count,
+            cap,
         );
     }
 }
 
-pub fn draw_wiper_knob(
-    knob: &Knob,
+pub fn draw_wiper_knob<T: KnobValue>(
+    knob: &Knob<T>,
     circle_color: &ColorSet,
     wiper_color: &ColorSet,
     track_color: &ColorSet,
 ) {
-    knob.draw_circle(0.7, circle_color, true, 32);
-    knob.draw_arc(
-        0.8,
-        0.41,
-        knob.angle_min,
-        knob.angle_max,
-        track_color,
-        16,
-        2,
-    );
-    if knob.t > 0.01 {
-        knob.draw_arc(0.8, 0.43, knob.angle_min, knob.angle, wiper_color, 16, 2);
-    }
+    KnobStyle::new(KnobVariant::Wiper).draw_wiper(knob, circle_color, wiper_color, track_color);
 }
 
-pub fn draw_wiper_only_knob(knob: &Knob, wiper_color: &ColorSet, track_color: &ColorSet) {
-    knob.draw_arc(
-        0.8,
-        0.41,
-        knob.angle_min,
-        knob.angle_max,
-        track_color,
-        32,
-        2,
-    );
-    if knob.t > 0.01 {
-        knob.draw_arc(0.8, 0.43, knob.angle_min, knob.angle, wiper_color, 16, 2);
-    }
+pub fn draw_wiper_only_knob<T: KnobValue>(
+    knob: &Knob<T>,
+    wiper_color: &ColorSet,
+    track_color: &ColorSet,
+) {
+    KnobStyle::new(KnobVariant::WiperOnly).draw_wiper_only(knob, wiper_color, track_color);
 }
 
-pub fn draw_wiper_dot_knob(
-    knob: &Knob,
+pub fn draw_wiper_dot_knob<T: KnobValue>(
+    knob: &Knob<T>,
     circle_color: &ColorSet,
     dot_color: &ColorSet,
     track_color: &ColorSet,
 ) {
-    knob.draw_circle(0.6, circle_color, true, 32);
-    knob.draw_arc(
-        0.85,
-        0.41,
-        knob.angle_min,
-        knob.angle_max,
+    KnobStyle::new(KnobVariant::WiperDot).draw_wiper_dot(
+        knob,
+        circle_color,
+        dot_color,
         track_color,
-        16,
-        2,
     );
-    knob.draw_dot(0.1, 0.85, knob.angle, dot_color, true, 12);
 }
 
-pub fn draw_tick_knob(knob: &Knob, circle_color: &ColorSet, tick_color: &ColorSet) {
-    knob.draw_circle(0.7, circle_color, true, 32);
-    knob.draw_tick(0.4, 0.7, 0.08, knob.angle, tick_color);
+pub fn draw_tick_knob<T: KnobValue>(
+    knob: &Knob<T>,
+    circle_color: &ColorSet,
+    tick_color: &ColorSet,
+) {
+    KnobStyle::new(KnobVariant::Tick).draw_tick(knob, circle_color, tick_color);
 }
 
-pub fn draw_dot_knob(knob: &Knob, circle_color: &ColorSet, dot_color: &ColorSet) {
-    knob.draw_circle(0.85, circle_color, true, 32);
-    knob.draw_dot(0.12, 0.6, knob.angle, dot_color, true, 12);
+pub fn draw_dot_knob<T: KnobValue>(knob: &Knob<T>, circle_color: &ColorSet, dot_color: &ColorSet) {
+    KnobStyle::new(KnobVariant::Dot).draw_dot(knob, circle_color, dot_color);
 }
 
-pub fn draw_space_knob(knob: &Knob, circle_color: &ColorSet, wiper_color: &ColorSet) {
-    knob.draw_circle(0.3 - knob.t * 0.1, circle_color, true, 16);
-    if knob.t > 0.01 {
-        knob.draw_arc(
-            0.4,
-            0.15,
-            knob.angle_min - 1.0,
-            knob.angle - 1.0,
-            wiper_color,
-            16,
-            2,
-        );
-
-        knob.draw_arc(
-            0.6,
-            0.15,
-            knob.angle_min + 1.0,
-            knob.angle + 1.0,
-            wiper_color,
-            16,
-            2,
-        );
-
-        knob.draw_arc(
-            0.8,
-            0.15,
-            knob.angle_min + 3.0,
-            knob.angle + 3.0,
-            wiper_color,
-            16,
-            2,
-        );
-    }
+pub fn draw_space_knob<T: KnobValue>(
+    knob: &Knob<T>,
+    circle_color: &ColorSet,
+    wiper_color: &ColorSet,
+) {
+    KnobStyle::new(KnobVariant::Space).draw_space(knob, circle_color, wiper_color);
 }
 
-pub fn draw_stepped_knob(
-    knob: &Knob,
+pub fn draw_stepped_knob<T: KnobValue>(
+    knob: &Knob<T>,
     steps: u32,
     circle_color: &ColorSet,
     dot_color: &ColorSet,
     step_color: &ColorSet,
 ) {
-    for n in 0..steps {
-        let a = n as f32 / (steps - 1) as f32;
-        let angle = knob.angle_min + (knob.angle_max - knob.angle_min) * a;
-        knob.draw_tick(0.7, 0.9, 0.04, angle, step_color);
+    KnobStyle::new(KnobVariant::Stepped(steps)).draw_stepped(
+        knob,
+        steps,
+        circle_color,
+        dot_color,
+        step_color,
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KnobVariant {
+    Wiper,
+    WiperOnly,
+    WiperDot,
+    Tick,
+    Dot,
+    Space,
+    Stepped(u32),
+}
+
+/// Colors used by the `draw_*_knob` functions when drawn through a `KnobVariant`.
+/// `base` fills the circle, `highlight` is used for the wiper, dot or tick and `lowlight`
+/// for the track and step ticks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KnobColors {
+    pub base: ColorSet,
+    pub highlight: ColorSet,
+    pub lowlight: ColorSet,
+}
+
+impl KnobColors {
+    pub fn new(base: ColorSet, highlight: ColorSet, lowlight: ColorSet) -> KnobColors {
+        KnobColors {
+            base,
+            highlight,
+            lowlight,
+        }
+    }
+}
+
+impl Default for KnobColors {
+    fn default() -> KnobColors {
+        KnobColors {
+            base: ColorSet::new(
+                [0.4, 0.3, 0.5, 1.0],
+                [0.45, 0.35, 0.55, 1.0],
+                [0.45, 0.35, 0.55, 1.0],
+            ),
+            highlight: ColorSet::new(
+                [0.4, 0.4, 0.8, 1.0],
+                [0.4, 0.4, 0.9, 1.0],
+                [0.5, 0.5, 1.0, 1.0],
+            ),
+            lowlight: ColorSet::from([0.0, 0.0, 0.0, 1.0]),
+        }
+    }
+}
+
+impl KnobVariant {
+    pub fn draw<T: KnobValue>(&self, knob: &Knob<T>, colors: &KnobColors) {
+        KnobStyle::new(*self).draw(knob, colors);
     }
-    knob.draw_circle(0.6, circle_color, true, 32);
-    knob.draw_dot(0.12, 0.4, knob.angle, dot_color, true, 12);
 }
 
 pub fn knob_title(ui: &Ui, label: &ImStr, width: f32) {
@@ -465,41 +626,49 @@ pub fn knob_title(ui: &Ui, label: &ImStr, width: f32) {
     ui.set_cursor_pos([old_cursor_pos[0], ui.cursor_pos()[1]]);
 }
 
-pub fn knob_with_drag<'a>(
+pub fn knob_with_drag<'a, T: KnobValue>(
     ui: &'a Ui,
     id: &'a ImStr,
     title: &ImStr,
-    p_value: &'a mut f32,
-    v_min: f32,
-    v_max: f32,
-    v_default: f32,
+    p_value: &'a mut T,
+    v_min: T,
+    v_max: T,
+    v_default: T,
     format: &ImStr,
-) -> Knob<'a> {
-    let width = ui.text_line_height() * 4.0;
-    let w = ui.push_item_width(width);
-
-    knob_title(ui, title, width);
-
-    let knob = Knob::new(ui, id, p_value, v_min, v_max, v_default, width * 0.5, true);
-
-    Drag::new(&ImString::new(format!(
-        "###{}_KNOB_DRAG_CONTORL_",
-        id.to_str()
-    )))
-    .range(v_min..=v_max)
-    .display_format(format)
-    .speed((v_max - v_min) / 1000.0)
-    .build(ui, knob.p_value);
+) -> Knob<'a, T> {
+    Knob::builder(id, p_value)
+        .range(v_min, v_max)
+        .default(v_default)
+        .title(title)
+        .drag(format)
+        .build(ui)
+}
 
-    w.pop(ui);
-    knob
+/// Same as `knob_with_drag`, with the drag widget showing the value through a formatter.
+pub fn knob_with_formatter<'a, T: KnobValue>(
+    ui: &'a Ui,
+    id: &'a ImStr,
+    title: &ImStr,
+    p_value: &'a mut T,
+    v_min: T,
+    v_max: T,
+    v_default: T,
+    formatter: &'a dyn ValueFormat,
+) -> Knob<'a, T> {
+    Knob::builder(id, p_value)
+        .range(v_min, v_max)
+        .default(v_default)
+        .title(title)
+        .drag(im_str!("%.3f"))
+        .formatter(formatter)
+        .build(ui)
 }
 
 pub fn hsv2rgb(hsva: [f32; 4]) -> [f32; 4] {
     let mut hsva = [
-        hsva[0].max(0.0).min(1.0),
-        hsva[1].max(0.0).min(1.0),
-        hsva[2].max(0.0).min(1.0),
+        hsva[0].clamp(0.0, 1.0),
+        hsva[1].clamp(0.0, 1.0),
+        hsva[2].clamp(0.0, 1.0),
         hsva[3],
     ];
 
@@ -0,0 +1,76 @@
+use crate::*;
+
+/// Draws the labels around the knob at each step angle, the selected one in `selected_color`.
+pub fn draw_selector_labels<T: KnobValue>(
+    knob: &Knob<T>,
+    labels: &[&ImStr],
+    selected: usize,
+    color: &ColorSet,
+    selected_color: &ColorSet,
+) {
+    let last = (labels.len().max(2) - 1) as f32;
+    for (n, label) in labels.iter().enumerate() {
+        let angle = knob.angle_min + (knob.angle_max - knob.angle_min) * n as f32 / last;
+        let color = if n == selected { selected_color } else { color };
+        knob.draw_text(label, 1.0, angle, color);
+    }
+}
+
+/// A rotary switch selecting one of `labels`, drawn as a stepped knob with the labels around
+/// it. Space for the labels is reserved around the knob. Returns the selected index, or `None`
+/// without labels. Nothing is drawn with fewer than two labels.
+pub fn selector_knob(
+    ui: &Ui,
+    id: &ImStr,
+    p_index: &mut usize,
+    labels: &[&ImStr],
+    radius: f32,
+    colors: &KnobColors,
+) -> Option<usize> {
+    let last = labels.len().checked_sub(1)?;
+    if last == 0 {
+        *p_index = 0;
+        return Some(0);
+    }
+    let steps = labels.len();
+    let label_size = labels.iter().fold([0.0f32, 0.0f32], |size, label| {
+        let label_size = ui.calc_text_size(label, false, -1.0);
+        [size[0].max(label_size[0]), size[1].max(label_size[1])]
+    });
+    let padding = [label_size[0] + 2.0, label_size[1] + 2.0];
+
+    let start = ui.cursor_screen_pos();
+    ui.set_cursor_screen_pos([start[0] + padding[0], start[1] + padding[1]]);
+
+    let mut value = (*p_index).min(steps - 1) as u32;
+    {
+        let knob = Knob::builder(id, &mut value)
+            .range(0, steps as u32 - 1)
+            .radius(radius)
+            .steps(Steps::Count(steps as u32))
+            .build(ui);
+        draw_stepped_knob(
+            &knob,
+            steps as u32,
+            &colors.base,
+            &colors.highlight,
+            &colors.lowlight,
+        );
+        draw_selector_labels(
+            &knob,
+            labels,
+            *knob.p_value as usize,
+            &current_knob_theme(ui).text,
+            &colors.highlight,
+        );
+    }
+
+    ui.set_cursor_screen_pos(start);
+    ui.dummy([
+        radius * 2.0 + padding[0] * 2.0,
+        radius * 2.0 + padding[1] * 2.0,
+    ]);
+
+    *p_index = (value as usize).min(last);
+    Some(*p_index)
+}
@@ -0,0 +1,115 @@
+use imgui::{im_str, sys, ImStr, Ui};
+
+/// Per knob values kept across frames in the current window's imgui state storage.
+pub(crate) struct KnobState {
+    storage: *mut sys::ImGuiStorage,
+    keys: [sys::ImGuiID; SLOTS.len()],
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Slot {
+    Editing,
+    EditFocus,
+    Adjusting,
+    Accumulator,
+    PositionLow,
+    PositionHigh,
+    EditStartLow,
+    EditStartHigh,
+    Fine,
+    Dragging,
+    DragRemainderLow,
+    DragRemainderHigh,
+    Wheel,
+    HasEditStart,
+}
+
+const SLOTS: [Slot; 14] = [
+    Slot::Editing,
+    Slot::EditFocus,
+    Slot::Adjusting,
+    Slot::Accumulator,
+    Slot::PositionLow,
+    Slot::PositionHigh,
+    Slot::EditStartLow,
+    Slot::EditStartHigh,
+    Slot::Fine,
+    Slot::Dragging,
+    Slot::DragRemainderLow,
+    Slot::DragRemainderHigh,
+    Slot::Wheel,
+    Slot::HasEditStart,
+];
+
+impl Slot {
+    fn name(self) -> &'static ImStr {
+        match self {
+            Slot::Editing => im_str!("##editing"),
+            Slot::EditFocus => im_str!("##edit_focus"),
+            Slot::Adjusting => im_str!("##adjusting"),
+            Slot::Accumulator => im_str!("##accumulator"),
+            Slot::PositionLow => im_str!("##position_low"),
+            Slot::PositionHigh => im_str!("##position_high"),
+            Slot::EditStartLow => im_str!("##edit_start_low"),
+            Slot::EditStartHigh => im_str!("##edit_start_high"),
+            Slot::Fine => im_str!("##fine"),
+            Slot::Dragging => im_str!("##dragging"),
+            Slot::DragRemainderLow => im_str!("##drag_remainder_low"),
+            Slot::DragRemainderHigh => im_str!("##drag_remainder_high"),
+            Slot::Wheel => im_str!("##wheel"),
+            Slot::HasEditStart => im_str!("##has_edit_start"),
+        }
+    }
+}
+
+impl KnobState {
+    /// Keys are ids of suffixed names under the knob's id, the same way child widgets get theirs.
+    pub(crate) fn new(_ui: &Ui, label: &ImStr) -> KnobState {
+        unsafe {
+            sys::igPushIDStr(label.as_ptr());
+            let keys = SLOTS.map(|slot| sys::igGetIDStr(slot.name().as_ptr()));
+            sys::igPopID();
+            KnobState {
+                storage: sys::igGetStateStorage(),
+                keys,
+            }
+        }
+    }
+
+    fn key(&self, slot: Slot) -> sys::ImGuiID {
+        self.keys[slot as usize]
+    }
+
+    pub(crate) fn get_bool(&self, slot: Slot) -> bool {
+        unsafe { sys::ImGuiStorage_GetBool(self.storage, self.key(slot), false) }
+    }
+
+    pub(crate) fn set_bool(&self, slot: Slot, value: bool) {
+        unsafe { sys::ImGuiStorage_SetBool(self.storage, self.key(slot), value) }
+    }
+
+    pub(crate) fn get_f32(&self, slot: Slot) -> f32 {
+        unsafe { sys::ImGuiStorage_GetFloat(self.storage, self.key(slot), 0.0) }
+    }
+
+    pub(crate) fn set_f32(&self, slot: Slot, value: f32) {
+        unsafe { sys::ImGuiStorage_SetFloat(self.storage, self.key(slot), value) }
+    }
+
+    /// Storage only holds 32 bit values, so doubles are split across two slots.
+    pub(crate) fn get_f64(&self, low: Slot, high: Slot) -> f64 {
+        unsafe {
+            let low = sys::ImGuiStorage_GetInt(self.storage, self.key(low), 0) as u32;
+            let high = sys::ImGuiStorage_GetInt(self.storage, self.key(high), 0);
+            f64::from_bits((high as u32 as u64) << 32 | low as u64)
+        }
+    }
+
+    pub(crate) fn set_f64(&self, low: Slot, high: Slot, value: f64) {
+        let bits = value.to_bits();
+        unsafe {
+            sys::ImGuiStorage_SetInt(self.storage, self.key(low), bits as u32 as i32);
+            sys::ImGuiStorage_SetInt(self.storage, self.key(high), (bits >> 32) as u32 as i32);
+        }
+    }
+}
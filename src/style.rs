@@ -0,0 +1,374 @@
+use crate::*;
+
+/// Proportions used to draw a knob, relative to the knob radius. Sizes of arcs and ticks
+/// are their thickness. `KnobStyle::from(variant)` gives the look of the `draw_*_knob`
+/// functions, which can then be adjusted field by field. When deserialized, missing fields
+/// take the `Wiper` proportions. The variant is written as its name, with the step count of
+/// `Stepped` in a separate `steps` field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct KnobStyle {
+    #[cfg_attr(feature = "serde", serde(flatten, with = "variant_fields"))]
+    pub variant: KnobVariant,
+    pub circle_size: f32,
+    /// How much the circle shrinks over the sweep, used by `Space`.
+    pub circle_shrink: f32,
+    pub circle_segments: u32,
+    pub track_radius: f32,
+    pub track_size: f32,
+    pub track_segments: u32,
+    pub wiper_radius: f32,
+    pub wiper_size: f32,
+    pub wiper_segments: u32,
+    pub arc_cap: ArcCap,
+    pub dot_size: f32,
+    pub dot_radius: f32,
+    pub dot_segments: u32,
+    pub tick_start: f32,
+    pub tick_end: f32,
+    pub tick_width: f32,
+    /// Radius and angle offset of each of the `Space` wiper rings.
+    pub rings: [(f32, f32); 3],
+}
+
+impl KnobStyle {
+    pub fn new(variant: KnobVariant) -> KnobStyle {
+        let base = KnobStyle {
+            variant,
+            circle_size: 0.7,
+            circle_shrink: 0.0,
+            circle_segments: 32,
+            track_radius: 0.8,
+            track_size: 0.41,
+            track_segments: 16,
+            wiper_radius: 0.8,
+            wiper_size: 0.43,
+            wiper_segments: 16,
+            arc_cap: ArcCap::Butt,
+            dot_size: 0.1,
+            dot_radius: 0.85,
+            dot_segments: 12,
+            tick_start: 0.4,
+            tick_end: 0.7,
+            tick_width: 0.08,
+            rings: [(0.4, -1.0), (0.6, 1.0), (0.8, 3.0)],
+        };
+        match variant {
+            KnobVariant::Wiper | KnobVariant::Tick => base,
+            KnobVariant::WiperOnly => KnobStyle {
+                track_segments: 32,
+                ..base
+            },
+            KnobVariant::WiperDot => KnobStyle {
+                circle_size: 0.6,
+                track_radius: 0.85,
+                ..base
+            },
+            KnobVariant::Dot => KnobStyle {
+                circle_size: 0.85,
+                dot_size: 0.12,
+                dot_radius: 0.6,
+                ..base
+            },
+            KnobVariant::Space => KnobStyle {
+                circle_size: 0.3,
+                circle_shrink: 0.1,
+                circle_segments: 16,
+                wiper_size: 0.15,
+                ..base
+            },
+            KnobVariant::Stepped(_) => KnobStyle {
+                circle_size: 0.6,
+                dot_size: 0.12,
+                dot_radius: 0.4,
+                tick_start: 0.7,
+                tick_end: 0.9,
+                tick_width: 0.04,
+                ..base
+            },
+        }
+    }
+
+    /// The layers drawing this style's variant.
+    pub fn layers(&self) -> Vec<KnobLayer> {
+        self.variant_layers(self.variant)
+    }
+
+    /// The layers drawing `variant` with these proportions.
+    pub fn variant_layers(&self, variant: KnobVariant) -> Vec<KnobLayer> {
+        let circle = KnobLayer::Circle {
+            size: self.circle_size,
+            shrink: self.circle_shrink,
+            filled: true,
+            segments: self.circle_segments,
+            color: LayerColor::Base,
+        };
+        let track = KnobLayer::Arc {
+            radius: self.track_radius,
+            size: self.track_size,
+            start: LayerAngle::Min,
+            end: LayerAngle::Max,
+            offset: 0.0,
+            segments: self.track_segments,
+            cap: self.arc_cap,
+            hide_at_origin: false,
+            color: LayerColor::Lowlight,
+        };
+        let wiper = |radius, offset| KnobLayer::Arc {
+            radius,
+            size: self.wiper_size,
+            start: LayerAngle::Origin,
+            end: LayerAngle::Value,
+            offset,
+            segments: self.wiper_segments,
+            cap: self.arc_cap,
+            hide_at_origin: true,
+            color: LayerColor::Highlight,
+        };
+        let dot = KnobLayer::Dot {
+            size: self.dot_size,
+            radius: self.dot_radius,
+            angle: LayerAngle::Value,
+            filled: true,
+            segments: self.dot_segments,
+            color: LayerColor::Highlight,
+        };
+
+        match variant {
+            KnobVariant::Wiper => vec![circle, track, wiper(self.wiper_radius, 0.0)],
+            KnobVariant::WiperOnly => vec![track, wiper(self.wiper_radius, 0.0)],
+            KnobVariant::WiperDot => vec![circle, track, dot],
+            KnobVariant::Tick => vec![
+                circle,
+                KnobLayer::Tick {
+                    start: self.tick_start,
+                    end: self.tick_end,
+                    width: self.tick_width,
+                    angle: LayerAngle::Value,
+                    color: LayerColor::Highlight,
+                },
+            ],
+            KnobVariant::Dot => vec![circle, dot],
+            KnobVariant::Space => {
+                let mut layers = vec![circle];
+                layers.extend(
+                    self.rings
+                        .iter()
+                        .map(|&(radius, offset)| wiper(radius, offset)),
+                );
+                layers
+            }
+            KnobVariant::Stepped(steps) => vec![
+                KnobLayer::StepTicks {
+                    steps,
+                    start: self.tick_start,
+                    end: self.tick_end,
+                    width: self.tick_width,
+                    color: LayerColor::Lowlight,
+                    selected_color: LayerColor::Highlight,
+                },
+                circle,
+                dot,
+            ],
+        }
+    }
+
+    pub fn draw<T: KnobValue>(&self, knob: &Knob<T>, colors: &KnobColors) {
+        draw_layers(knob, &self.layers(), colors);
+    }
+
+    fn draw_variant<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        variant: KnobVariant,
+        base: &ColorSet,
+        highlight: &ColorSet,
+        lowlight: &ColorSet,
+    ) {
+        let colors = KnobColors::new(*base, *highlight, *lowlight);
+        draw_layers(knob, &self.variant_layers(variant), &colors);
+    }
+
+    pub fn draw_wiper<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        circle_color: &ColorSet,
+        wiper_color: &ColorSet,
+        track_color: &ColorSet,
+    ) {
+        self.draw_variant(
+            knob,
+            KnobVariant::Wiper,
+            circle_color,
+            wiper_color,
+            track_color,
+        );
+    }
+
+    pub fn draw_wiper_only<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        wiper_color: &ColorSet,
+        track_color: &ColorSet,
+    ) {
+        self.draw_variant(
+            knob,
+            KnobVariant::WiperOnly,
+            wiper_color,
+            wiper_color,
+            track_color,
+        );
+    }
+
+    pub fn draw_wiper_dot<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        circle_color: &ColorSet,
+        dot_color: &ColorSet,
+        track_color: &ColorSet,
+    ) {
+        self.draw_variant(
+            knob,
+            KnobVariant::WiperDot,
+            circle_color,
+            dot_color,
+            track_color,
+        );
+    }
+
+    pub fn draw_tick<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        circle_color: &ColorSet,
+        tick_color: &ColorSet,
+    ) {
+        self.draw_variant(
+            knob,
+            KnobVariant::Tick,
+            circle_color,
+            tick_color,
+            tick_color,
+        );
+    }
+
+    pub fn draw_dot<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        circle_color: &ColorSet,
+        dot_color: &ColorSet,
+    ) {
+        self.draw_variant(knob, KnobVariant::Dot, circle_color, dot_color, dot_color);
+    }
+
+    pub fn draw_space<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        circle_color: &ColorSet,
+        wiper_color: &ColorSet,
+    ) {
+        self.draw_variant(
+            knob,
+            KnobVariant::Space,
+            circle_color,
+            wiper_color,
+            wiper_color,
+        );
+    }
+
+    pub fn draw_stepped<T: KnobValue>(
+        &self,
+        knob: &Knob<T>,
+        steps: u32,
+        circle_color: &ColorSet,
+        dot_color: &ColorSet,
+        step_color: &ColorSet,
+    ) {
+        self.draw_variant(
+            knob,
+            KnobVariant::Stepped(steps),
+            circle_color,
+            dot_color,
+            step_color,
+        );
+    }
+}
+
+impl From<KnobVariant> for KnobStyle {
+    fn from(variant: KnobVariant) -> KnobStyle {
+        KnobStyle::new(variant)
+    }
+}
+
+impl Default for KnobStyle {
+    fn default() -> KnobStyle {
+        KnobStyle::new(KnobVariant::Wiper)
+    }
+}
+
+/// TOML can't write enum variants holding data, so `Stepped(n)` is split into
+/// `variant = "Stepped"` and `steps = n`.
+#[cfg(feature = "serde")]
+mod variant_fields {
+    use crate::KnobVariant;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    enum Name {
+        Wiper,
+        WiperOnly,
+        WiperDot,
+        Tick,
+        Dot,
+        Space,
+        Stepped,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Fields {
+        #[serde(default)]
+        variant: Option<Name>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        steps: Option<u32>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        variant: &KnobVariant,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let (name, steps) = match *variant {
+            KnobVariant::Wiper => (Name::Wiper, None),
+            KnobVariant::WiperOnly => (Name::WiperOnly, None),
+            KnobVariant::WiperDot => (Name::WiperDot, None),
+            KnobVariant::Tick => (Name::Tick, None),
+            KnobVariant::Dot => (Name::Dot, None),
+            KnobVariant::Space => (Name::Space, None),
+            KnobVariant::Stepped(steps) => (Name::Stepped, Some(steps)),
+        };
+        Fields {
+            variant: Some(name),
+            steps,
+        }
+        .serialize(serializer)
+    }
+
+    /// A missing variant is `Wiper`, the variant of `KnobStyle::default()`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<KnobVariant, D::Error> {
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(match fields.variant.unwrap_or(Name::Wiper) {
+            Name::Wiper => KnobVariant::Wiper,
+            Name::WiperOnly => KnobVariant::WiperOnly,
+            Name::WiperDot => KnobVariant::WiperDot,
+            Name::Tick => KnobVariant::Tick,
+            Name::Dot => KnobVariant::Dot,
+            Name::Space => KnobVariant::Space,
+            Name::Stepped => KnobVariant::Stepped(
+                fields
+                    .steps
+                    .ok_or_else(|| de::Error::missing_field("steps"))?,
+            ),
+        })
+    }
+}
@@ -0,0 +1,283 @@
+/// Maps a value in `v_min..v_max` to a normalized knob position `t` in `0.0..=1.0` and back.
+///
+/// The same taper is used for the drag math, for the wiper angle and for the drag widget
+/// speed, so a knob feels consistent across its whole sweep.
+#[derive(Clone, Copy, Default)]
+pub enum Taper<'a> {
+    #[default]
+    Linear,
+    /// Equal ratios get equal travel. Needs `v_min` and `v_max` to share the same sign,
+    /// otherwise falls back to linear.
+    Logarithmic,
+    /// `value = v_min + range * (e^(k * t) - 1) / (e^k - 1)`, positive `k` gives finer
+    /// control near `v_min`.
+    Exponential(f32),
+    /// For values in dB, the knob travel is linear in amplitude.
+    Decibel,
+    /// Skewed power curve that puts the given value at the middle of the sweep.
+    Skew(f64),
+    /// User supplied curves operating on linear positions in `0.0..=1.0`:
+    /// the first maps a linear position to `t`, the second maps `t` back.
+    Custom(&'a dyn Fn(f32) -> f32, &'a dyn Fn(f32) -> f32),
+}
+
+fn db_to_amplitude(db: f64) -> f64 {
+    10.0f64.powf(db / 20.0)
+}
+
+fn amplitude_to_db(amplitude: f64) -> f64 {
+    20.0 * amplitude.max(1e-12).log10()
+}
+
+impl<'a> Taper<'a> {
+    pub fn normalize(&self, value: f64, v_min: f64, v_max: f64) -> f32 {
+        let linear = (value - v_min) / (v_max - v_min);
+        let t = match *self {
+            Taper::Linear => linear,
+            Taper::Logarithmic => {
+                if v_min * v_max <= 0.0 {
+                    linear
+                } else {
+                    (value / v_min).ln() / (v_max / v_min).ln()
+                }
+            }
+            Taper::Exponential(k) => {
+                let k = k as f64;
+                if k.abs() < 1e-6 {
+                    linear
+                } else {
+                    (1.0 + linear.clamp(0.0, 1.0) * (k.exp() - 1.0)).ln() / k
+                }
+            }
+            Taper::Decibel => {
+                let a_min = db_to_amplitude(v_min);
+                let a_max = db_to_amplitude(v_max);
+                (db_to_amplitude(value) - a_min) / (a_max - a_min)
+            }
+            Taper::Skew(center) => linear
+                .clamp(0.0, 1.0)
+                .powf(1.0 / skew_exponent(center, v_min, v_max)),
+            Taper::Custom(to_t, _) => to_t(linear.clamp(0.0, 1.0) as f32) as f64,
+        };
+        if t.is_nan() {
+            0.0
+        } else {
+            t.clamp(0.0, 1.0) as f32
+        }
+    }
+
+    pub fn denormalize(&self, t: f32, v_min: f64, v_max: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0) as f64;
+        let range = v_max - v_min;
+        let value = match *self {
+            Taper::Linear => v_min + range * t,
+            Taper::Logarithmic => {
+                if v_min * v_max <= 0.0 {
+                    v_min + range * t
+                } else {
+                    v_min * (v_max / v_min).powf(t)
+                }
+            }
+            Taper::Exponential(k) => {
+                let k = k as f64;
+                if k.abs() < 1e-6 {
+                    v_min + range * t
+                } else {
+                    v_min + range * ((k * t).exp() - 1.0) / (k.exp() - 1.0)
+                }
+            }
+            Taper::Decibel => {
+                let a_min = db_to_amplitude(v_min);
+                let a_max = db_to_amplitude(v_max);
+                amplitude_to_db(a_min + (a_max - a_min) * t)
+            }
+            Taper::Skew(center) => v_min + range * t.powf(skew_exponent(center, v_min, v_max)),
+            Taper::Custom(_, from_t) => v_min + range * from_t(t as f32).clamp(0.0, 1.0) as f64,
+        };
+        value.max(v_min.min(v_max)).min(v_max.max(v_min))
+    }
+
+    /// Value change for a small step of `t` around the given value, used to scale
+    /// linear-speed widgets such as `Drag` to the local slope of the taper.
+    pub fn slope(&self, value: f64, v_min: f64, v_max: f64) -> f64 {
+        let dt = 0.001;
+        let t = self.normalize(value, v_min, v_max);
+        let (t0, t1) = if t + dt <= 1.0 {
+            (t, t + dt)
+        } else {
+            (t - dt, t)
+        };
+        (self.denormalize(t1, v_min, v_max) - self.denormalize(t0, v_min, v_max)).abs() / dt as f64
+    }
+}
+
+fn skew_exponent(center: f64, v_min: f64, v_max: f64) -> f64 {
+    let mid = (center - v_min) / (v_max - v_min);
+    if mid <= 0.0 || mid >= 1.0 || mid.is_nan() {
+        1.0
+    } else {
+        mid.ln() / 0.5f64.ln()
+    }
+}
+
+/// Quantization applied to knob values.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum Steps {
+    #[default]
+    Continuous,
+    /// Number of positions evenly spread over the sweep, including both ends.
+    Count(u32),
+    /// Fixed value increment, counted from `v_min`.
+    Increment(f64),
+}
+
+impl Steps {
+    pub fn is_continuous(&self) -> bool {
+        match *self {
+            Steps::Continuous => true,
+            Steps::Count(n) => n < 2,
+            Steps::Increment(inc) => inc <= 0.0,
+        }
+    }
+
+    pub fn snap(&self, value: f64, v_min: f64, v_max: f64, taper: &Taper) -> f64 {
+        if self.is_continuous() {
+            return value;
+        }
+        match *self {
+            Steps::Count(n) => {
+                let last = (n - 1) as f32;
+                let t = taper.normalize(value, v_min, v_max);
+                taper.denormalize((t * last).round() / last, v_min, v_max)
+            }
+            Steps::Increment(inc) => {
+                let snapped = v_min + ((value - v_min) / inc).round() * inc;
+                snapped.max(v_min.min(v_max)).min(v_max.max(v_min))
+            }
+            Steps::Continuous => value,
+        }
+    }
+
+    /// Moves `value` by `count` steps. Continuous values move by `t_step` of the sweep per step.
+    pub fn offset(
+        &self,
+        value: f64,
+        count: f32,
+        t_step: f32,
+        v_min: f64,
+        v_max: f64,
+        taper: &Taper,
+    ) -> f64 {
+        match *self {
+            Steps::Count(n) if n >= 2 => {
+                let t = taper.normalize(value, v_min, v_max) + count / (n - 1) as f32;
+                self.snap(taper.denormalize(t, v_min, v_max), v_min, v_max, taper)
+            }
+            Steps::Increment(inc) if inc > 0.0 => {
+                let direction = if v_max < v_min { -1.0 } else { 1.0 };
+                self.snap(value + count as f64 * inc * direction, v_min, v_max, taper)
+            }
+            _ => {
+                let t = taper.normalize(value, v_min, v_max) + count * t_step;
+                taper.denormalize(t, v_min, v_max)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(taper: Taper, v_min: f64, v_max: f64) {
+        for i in 0..=10 {
+            let value = v_min + (v_max - v_min) * i as f64 / 10.0;
+            let t = taper.normalize(value, v_min, v_max);
+            assert!((0.0..=1.0).contains(&t));
+            let back = taper.denormalize(t, v_min, v_max);
+            assert!(
+                (back - value).abs() <= (v_max - v_min).abs() * 1e-4,
+                "{} -> {} -> {}",
+                value,
+                t,
+                back
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let square = |x: f32| x * x;
+        let root = |t: f32| t.sqrt();
+        assert_round_trip(Taper::Linear, -6.0, 6.0);
+        assert_round_trip(Taper::Linear, 10.0, 0.0);
+        assert_round_trip(Taper::Logarithmic, 20.0, 20000.0);
+        assert_round_trip(Taper::Exponential(4.0), 0.0, 1.0);
+        assert_round_trip(Taper::Exponential(-4.0), 0.0, 1.0);
+        assert_round_trip(Taper::Decibel, -60.0, 6.0);
+        assert_round_trip(Taper::Skew(1000.0), 20.0, 20000.0);
+        assert_round_trip(Taper::Custom(&square, &root), 0.0, 100.0);
+    }
+
+    #[test]
+    fn ends_and_clamping() {
+        for (taper, v_min, v_max) in [
+            (Taper::Linear, 20.0, 20000.0),
+            (Taper::Logarithmic, 20.0, 20000.0),
+            (Taper::Decibel, -60.0, 6.0),
+        ] {
+            assert_eq!(taper.normalize(v_min, v_min, v_max), 0.0);
+            assert_eq!(taper.normalize(v_max, v_min, v_max), 1.0);
+            assert_eq!(taper.normalize(v_max + 10.0, v_min, v_max), 1.0);
+            assert!((taper.denormalize(2.0, v_min, v_max) - v_max).abs() < 1e-9);
+        }
+        assert_eq!(Taper::Linear.normalize(1.0, 1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn logarithmic_midpoint() {
+        let t = Taper::Logarithmic.normalize(100.0, 10.0, 1000.0);
+        assert!((t - 0.5).abs() < 1e-6);
+        let mid = Taper::Skew(1000.0).denormalize(0.5, 20.0, 20000.0);
+        assert!((mid - 1000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn snap() {
+        let taper = Taper::Linear;
+        assert_eq!(Steps::Continuous.snap(0.33, 0.0, 1.0, &taper), 0.33);
+        assert_eq!(Steps::Count(1).snap(0.33, 0.0, 1.0, &taper), 0.33);
+        assert_eq!(Steps::Count(5).snap(0.3, 0.0, 1.0, &taper), 0.25);
+        assert_eq!(Steps::Count(5).snap(0.9, 0.0, 1.0, &taper), 1.0);
+        assert_eq!(Steps::Increment(0.5).snap(1.3, -6.0, 6.0, &taper), 1.5);
+        assert_eq!(Steps::Increment(5.0).snap(9.0, 0.0, 12.0, &taper), 10.0);
+        assert_eq!(Steps::Increment(5.0).snap(14.0, 0.0, 12.0, &taper), 12.0);
+    }
+
+    #[test]
+    fn offset() {
+        let taper = Taper::Linear;
+        assert_eq!(
+            Steps::Count(5).offset(0.25, 1.0, 0.0, 0.0, 1.0, &taper),
+            0.5
+        );
+        assert_eq!(
+            Steps::Count(5).offset(0.25, -3.0, 0.0, 0.0, 1.0, &taper),
+            0.0
+        );
+        assert_eq!(
+            Steps::Increment(1.0).offset(3.0, 2.0, 0.0, 0.0, 10.0, &taper),
+            5.0
+        );
+        assert_eq!(
+            Steps::Increment(1.0).offset(3.0, 2.0, 0.0, 10.0, 0.0, &taper),
+            1.0
+        );
+        assert_eq!(
+            Steps::Increment(1.0).offset(9.0, 10.0, 0.0, 0.0, 10.0, &taper),
+            10.0
+        );
+        let value = Steps::Continuous.offset(0.5, 1.0, 0.1, 0.0, 2.0, &taper);
+        assert!((value - 0.7).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::*;
+use std::cell::RefCell;
+#[cfg(feature = "toml")]
+use std::{fmt, fs, io, path::Path};
+
+impl ColorSet {
+    /// Colors taken from an imgui style, such as one from `ui.clone_style()`.
+    pub fn from_style(
+        style: &Style,
+        base: StyleColor,
+        hovered: StyleColor,
+        active: StyleColor,
+    ) -> ColorSet {
+        ColorSet::new(style[base], style[hovered], style[active])
+    }
+}
+
+impl KnobColors {
+    /// Knob colors following an imgui style: the circle uses the `FrameBg` colors, the
+    /// wiper `SliderGrab`, `ButtonHovered` and `SliderGrabActive`, the track `TextDisabled`.
+    pub fn from_style(style: &Style) -> KnobColors {
+        KnobColors {
+            base: ColorSet::from_style(
+                style,
+                StyleColor::FrameBg,
+                StyleColor::FrameBgHovered,
+                StyleColor::FrameBgActive,
+            ),
+            highlight: ColorSet::from_style(
+                style,
+                StyleColor::SliderGrab,
+                StyleColor::ButtonHovered,
+                StyleColor::SliderGrabActive,
+            ),
+            lowlight: ColorSet::from(style[StyleColor::TextDisabled]),
+        }
+    }
+}
+
+/// Colors shared by a group of knobs, made current with `push_knob_theme`. Knobs built
+/// without their own colors use the current theme.
+///
+/// With the `toml` feature a whole knob skin can be loaded from a file, see
+/// `KnobTheme::load` and `KnobBuilder::theme`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KnobTheme {
+    pub colors: KnobColors,
+    /// Used by knobs built with `KnobBuilder::theme`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub style: KnobStyle,
+    /// Ring drawn around knobs with keyboard or gamepad focus.
+    pub focus: ColorSet,
+    /// Labels around selector knobs.
+    pub text: ColorSet,
+}
+
+impl KnobTheme {
+    /// Focus and text colors are those of imgui's default dark style.
+    pub fn new(colors: KnobColors) -> KnobTheme {
+        KnobTheme {
+            colors,
+            style: KnobStyle::default(),
+            focus: ColorSet::from([0.26, 0.59, 0.98, 1.0]),
+            text: ColorSet::from([1.0, 1.0, 1.0, 1.0]),
+        }
+    }
+
+    pub fn from_style(style: &Style) -> KnobTheme {
+        KnobTheme {
+            colors: KnobColors::from_style(style),
+            style: KnobStyle::default(),
+            focus: ColorSet::from(style[StyleColor::NavHighlight]),
+            text: ColorSet::from(style[StyleColor::Text]),
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl KnobTheme {
+    pub fn from_toml(text: &str) -> Result<KnobTheme, ThemeError> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String, ThemeError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Reads a theme from a TOML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<KnobTheme, ThemeError> {
+        KnobTheme::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ThemeError> {
+        Ok(fs::write(path, self.to_toml()?)?)
+    }
+}
+
+/// Error reading, parsing or writing a theme file.
+#[cfg(feature = "toml")]
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Write(toml::ser::Error),
+}
+
+#[cfg(feature = "toml")]
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::Io(error) => write!(f, "theme file: {}", error),
+            ThemeError::Parse(error) => write!(f, "theme parse: {}", error),
+            ThemeError::Write(error) => write!(f, "theme write: {}", error),
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl std::error::Error for ThemeError {}
+
+#[cfg(feature = "toml")]
+impl From<io::Error> for ThemeError {
+    fn from(error: io::Error) -> ThemeError {
+        ThemeError::Io(error)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for ThemeError {
+    fn from(error: toml::de::Error) -> ThemeError {
+        ThemeError::Parse(error)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::ser::Error> for ThemeError {
+    fn from(error: toml::ser::Error) -> ThemeError {
+        ThemeError::Write(error)
+    }
+}
+
+thread_local! {
+    static THEME_STACK: RefCell<Vec<KnobTheme>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes `theme` current until the returned token is popped.
+pub fn push_knob_theme(_ui: &Ui, theme: KnobTheme) -> KnobThemeToken {
+    THEME_STACK.with(|stack| stack.borrow_mut().push(theme));
+    KnobThemeToken { _private: () }
+}
+
+/// The most recently pushed theme, or one following the imgui style when none is pushed.
+pub fn current_knob_theme(ui: &Ui) -> KnobTheme {
+    THEME_STACK
+        .with(|stack| stack.borrow().last().copied())
+        .unwrap_or_else(|| KnobTheme::from_style(&ui.clone_style()))
+}
+
+/// Restores the previous theme when popped or dropped.
+#[must_use]
+pub struct KnobThemeToken {
+    _private: (),
+}
+
+impl KnobThemeToken {
+    pub fn pop(self, _ui: &Ui) {}
+}
+
+impl Drop for KnobThemeToken {
+    fn drop(&mut self) {
+        THEME_STACK.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_round_trip() {
+        for variant in [
+            KnobVariant::Wiper,
+            KnobVariant::WiperOnly,
+            KnobVariant::WiperDot,
+            KnobVariant::Tick,
+            KnobVariant::Dot,
+            KnobVariant::Space,
+            KnobVariant::Stepped(7),
+        ] {
+            let mut theme = KnobTheme::new(KnobColors::default());
+            theme.style = KnobStyle::new(variant);
+            let text = theme.to_toml().unwrap();
+            assert_eq!(KnobTheme::from_toml(&text).unwrap(), theme, "{}", text);
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("knob_theme_{}.toml", std::process::id()));
+        let mut theme = KnobTheme::new(KnobColors::default());
+        theme.style = KnobStyle::new(KnobVariant::Stepped(5));
+        theme.save(&path).unwrap();
+        let loaded = KnobTheme::load(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.unwrap(), theme);
+    }
+
+    #[test]
+    fn partial_style() {
+        let mut text = KnobTheme::new(KnobColors::default()).to_toml().unwrap();
+        let start = text.find("[style]").unwrap();
+        let end = text[start..]
+            .find("\n\n")
+            .map_or(text.len(), |end| start + end);
+        text.replace_range(
+            start..end,
+            "[style]\nvariant = \"Stepped\"\nsteps = 4\ncircle_size = 0.5",
+        );
+        let style = KnobTheme::from_toml(&text).unwrap().style;
+        assert_eq!(style.variant, KnobVariant::Stepped(4));
+        assert_eq!(style.circle_size, 0.5);
+        assert_eq!(style.track_size, KnobStyle::default().track_size);
+
+        let missing_steps = text.replace("steps = 4\n", "");
+        assert!(KnobTheme::from_toml(&missing_steps).is_err());
+    }
+}
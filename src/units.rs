@@ -0,0 +1,344 @@
+use crate::*;
+
+/// Parses a typed knob value, a number with an optional SI prefix and unit such as
+/// "440hz", "-3db", "1.5k", "250ms" or "50%".
+///
+/// Hz, dB and seconds are taken as the knob's own units, percentages are a position on the
+/// knob's sweep. Knobs holding something else, such as an amplitude shown in dB, convert
+/// with a `ValueFormat`.
+pub fn parse_value(text: &str, taper: &Taper, v_min: f64, v_max: f64) -> Option<f64> {
+    let (number, suffix) = split_number(text)?;
+    let (scale, unit) = split_unit(suffix)?;
+    let value = number * scale;
+    Some(match unit.to_ascii_lowercase().as_str() {
+        "%" => taper.denormalize((value / 100.0) as f32, v_min, v_max),
+        _ => value,
+    })
+}
+
+fn is_unit(text: &str) -> bool {
+    ["", "hz", "db", "s", "%"]
+        .iter()
+        .any(|unit| text.eq_ignore_ascii_case(unit))
+}
+
+/// Splits a suffix into the scale of its SI prefix and the unit.
+fn split_unit(suffix: &str) -> Option<(f64, &str)> {
+    if is_unit(suffix) {
+        return Some((1.0, suffix));
+    }
+    let mut chars = suffix.chars();
+    let scale = match chars.next()? {
+        'G' => 1e9,
+        'M' => 1e6,
+        'k' | 'K' => 1e3,
+        'm' => 1e-3,
+        'u' | 'µ' => 1e-6,
+        _ => return None,
+    };
+    let unit = chars.as_str();
+    if is_unit(unit) {
+        Some((scale, unit))
+    } else {
+        None
+    }
+}
+
+/// Converts knob values to text for display and parses typed text back, used by the drag
+/// widget, the text input and copy and paste.
+pub trait ValueFormat {
+    fn format(&self, value: f64) -> String;
+    /// Returns `None` for text it doesn't understand, which is then tried with `parse_value`.
+    fn parse(&self, text: &str) -> Option<f64>;
+}
+
+/// Splits text into its leading number and the rest.
+fn split_number(text: &str) -> Option<(f64, &str)> {
+    let text = text.trim();
+    let (number, suffix) = (1..=text.len())
+        .rev()
+        .filter(|&i| text.is_char_boundary(i))
+        .find_map(|i| text[..i].parse::<f64>().ok().map(|n| (n, text[i..].trim())))?;
+    if number.is_finite() {
+        Some((number, suffix))
+    } else {
+        None
+    }
+}
+
+/// A number with an optional SI prefix, followed by `unit` or nothing.
+fn parse_unit(text: &str, unit: &str) -> Option<f64> {
+    let (number, suffix) = split_number(text)?;
+    let (scale, suffix) = split_unit(suffix)?;
+    if suffix.is_empty() || suffix.eq_ignore_ascii_case(unit) {
+        Some(number * scale)
+    } else {
+        None
+    }
+}
+
+/// Values in dB, such as "-3.0 dB". Values at or below -144 dB show as "-inf dB".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecibelFormat {
+    pub precision: usize,
+}
+
+impl Default for DecibelFormat {
+    fn default() -> DecibelFormat {
+        DecibelFormat { precision: 1 }
+    }
+}
+
+impl ValueFormat for DecibelFormat {
+    fn format(&self, value: f64) -> String {
+        if value <= -144.0 {
+            "-inf dB".to_string()
+        } else {
+            format!("{:.*} dB", self.precision, value)
+        }
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        if text.to_ascii_lowercase().starts_with("-inf") {
+            Some(f64::MIN)
+        } else {
+            parse_unit(text, "db")
+        }
+    }
+}
+
+/// Frequencies in Hz, such as "880 Hz" or "1.20 kHz".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct HertzFormat;
+
+impl ValueFormat for HertzFormat {
+    fn format(&self, value: f64) -> String {
+        if value.abs() >= 1000.0 {
+            format!("{:.2} kHz", value / 1000.0)
+        } else if value.abs() >= 100.0 {
+            format!("{:.0} Hz", value)
+        } else {
+            format!("{:.1} Hz", value)
+        }
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        parse_unit(text, "hz")
+    }
+}
+
+/// Times in seconds, shown in milliseconds below one second, such as "250 ms" or "1.50 s".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TimeFormat;
+
+impl ValueFormat for TimeFormat {
+    fn format(&self, value: f64) -> String {
+        if value.abs() >= 1.0 {
+            format!("{:.2} s", value)
+        } else if value.abs() >= 0.01 {
+            format!("{:.0} ms", value * 1000.0)
+        } else {
+            format!("{:.1} ms", value * 1000.0)
+        }
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        parse_unit(text, "s")
+    }
+}
+
+/// Fractions shown as percentages, 0.5 is "50%". Typed numbers are percentages with or
+/// without the sign.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PercentFormat {
+    pub precision: usize,
+}
+
+impl ValueFormat for PercentFormat {
+    fn format(&self, value: f64) -> String {
+        format!("{:.*}%", self.precision, value * 100.0)
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        parse_unit(text, "%").map(|percent| percent / 100.0)
+    }
+}
+
+/// Pan positions from -1.0 to 1.0 shown as "L 30", "C" and "R 30". Typed plain numbers
+/// are percentages, negative to the left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PanFormat;
+
+impl ValueFormat for PanFormat {
+    fn format(&self, value: f64) -> String {
+        let percent = (value * 100.0).round();
+        if percent < 0.0 {
+            format!("L {:.0}", -percent)
+        } else if percent > 0.0 {
+            format!("R {:.0}", percent)
+        } else {
+            "C".to_string()
+        }
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let mut chars = text.chars();
+        let direction = match chars.next()?.to_ascii_uppercase() {
+            'C' if text.len() == 1 || text.eq_ignore_ascii_case("center") => return Some(0.0),
+            'L' => -1.0,
+            'R' => 1.0,
+            _ => return split_number(text).map(|(number, _)| number / 100.0),
+        };
+        let (number, _) = split_number(chars.as_str())?;
+        Some(direction * number / 100.0)
+    }
+}
+
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// MIDI note numbers shown as note names with middle C, note 60, as "C4". Takes names
+/// with `#` or `b`, such as "Eb2", or plain note numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct NoteFormat;
+
+impl ValueFormat for NoteFormat {
+    fn format(&self, value: f64) -> String {
+        let note = value.round() as i32;
+        format!(
+            "{}{}",
+            NOTE_NAMES[note.rem_euclid(12) as usize],
+            note.div_euclid(12) - 1
+        )
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        if let Ok(note) = text.parse::<f64>() {
+            return Some(note);
+        }
+        let mut chars = text.chars();
+        let base = match chars.next()?.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => return None,
+        };
+        let rest = chars.as_str();
+        let (accidental, rest) = match rest.chars().next() {
+            Some('#') => (1, &rest[1..]),
+            Some('b') => (-1, &rest[1..]),
+            _ => (0, rest),
+        };
+        let octave: i32 = rest.trim().parse().ok()?;
+        Some(((octave + 1) * 12 + base + accidental) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Option<f64> {
+        parse_value(text, &Taper::Linear, -6.0, 6.0)
+    }
+
+    fn assert_close(value: Option<f64>, expected: f64) {
+        let value = value.unwrap();
+        assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn units() {
+        assert_close(parse("440hz"), 440.0);
+        assert_close(parse("2.5 kHz"), 2500.0);
+        assert_close(parse("1.5k"), 1500.0);
+        assert_close(parse("250ms"), 0.25);
+        assert_close(parse("-3db"), -3.0);
+        assert_close(parse(" -3 dB "), -3.0);
+        assert_close(parse("1e3"), 1000.0);
+        assert_close(parse("-1.25"), -1.25);
+    }
+
+    #[test]
+    fn percent_is_position() {
+        assert_close(parse("50%"), 0.0);
+        assert_close(parse("100 %"), 6.0);
+        assert_close(parse_value("50%", &Taper::Logarithmic, 10.0, 1000.0), 100.0);
+    }
+
+    #[test]
+    fn rejects() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("abc"), None);
+        assert_eq!(parse("3 volts"), None);
+        assert_eq!(parse("inf"), None);
+        assert_eq!(parse("nan"), None);
+    }
+
+    fn assert_format(format: &dyn ValueFormat, value: f64, text: &str) {
+        assert_eq!(format.format(value), text);
+        assert_close(format.parse(text), value);
+    }
+
+    #[test]
+    fn decibel_format() {
+        let format = DecibelFormat::default();
+        assert_format(&format, -3.0, "-3.0 dB");
+        assert_format(&DecibelFormat { precision: 2 }, 1.25, "1.25 dB");
+        assert_eq!(format.format(-200.0), "-inf dB");
+        assert_eq!(format.parse("-inf dB"), Some(f64::MIN));
+        assert_close(format.parse("-6"), -6.0);
+        assert_eq!(format.parse("6 Hz"), None);
+    }
+
+    #[test]
+    fn hertz_format() {
+        assert_format(&HertzFormat, 1200.0, "1.20 kHz");
+        assert_format(&HertzFormat, 440.0, "440 Hz");
+        assert_format(&HertzFormat, 55.5, "55.5 Hz");
+        assert_close(HertzFormat.parse("2k"), 2000.0);
+    }
+
+    #[test]
+    fn time_format() {
+        assert_format(&TimeFormat, 0.25, "250 ms");
+        assert_format(&TimeFormat, 1.5, "1.50 s");
+        assert_format(&TimeFormat, 0.0025, "2.5 ms");
+        assert_close(TimeFormat.parse("2"), 2.0);
+    }
+
+    #[test]
+    fn percent_format() {
+        assert_format(&PercentFormat { precision: 0 }, 0.5, "50%");
+        assert_format(&PercentFormat { precision: 1 }, 0.125, "12.5%");
+        assert_close(PercentFormat::default().parse("75"), 0.75);
+    }
+
+    #[test]
+    fn pan_format() {
+        assert_format(&PanFormat, -0.3, "L 30");
+        assert_format(&PanFormat, 0.0, "C");
+        assert_format(&PanFormat, 1.0, "R 100");
+        assert_close(PanFormat.parse("-50"), -0.5);
+        assert_close(PanFormat.parse("center"), 0.0);
+    }
+
+    #[test]
+    fn note_format() {
+        assert_format(&NoteFormat, 60.0, "C4");
+        assert_format(&NoteFormat, 61.0, "C#4");
+        assert_format(&NoteFormat, 21.0, "A0");
+        assert_format(&NoteFormat, 0.0, "C-1");
+        assert_close(NoteFormat.parse("Eb2"), 39.0);
+        assert_close(NoteFormat.parse("64"), 64.0);
+        assert_eq!(NoteFormat.parse("H2"), None);
+    }
+}
@@ -0,0 +1,45 @@
+use imgui::internal::DataTypeKind;
+use std::fmt::Display;
+
+/// Numeric types a knob can control. Knob math is done in `f64`, `from_f64` rounds to the
+/// nearest integer for integer types and saturates at the type's bounds.
+pub trait KnobValue: DataTypeKind + PartialEq + PartialOrd + Display {
+    const IS_INTEGER: bool;
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! impl_knob_value_int {
+    ($($t:ty),*) => {
+        $(
+            impl KnobValue for $t {
+                const IS_INTEGER: bool = true;
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+                fn from_f64(value: f64) -> Self {
+                    value.round() as $t
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_knob_value_float {
+    ($($t:ty),*) => {
+        $(
+            impl KnobValue for $t {
+                const IS_INTEGER: bool = false;
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+                fn from_f64(value: f64) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_knob_value_int!(i32, u32, i64);
+impl_knob_value_float!(f32, f64);